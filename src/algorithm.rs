@@ -0,0 +1,196 @@
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::moves::*;
+use crate::rubiks::RubiksCube3x3;
+
+/// A sequence of moves, written in standard notation with the moves
+/// separated by spaces, e.g. `R U R' U'`.
+#[derive(PartialEq, Eq, Clone, Debug, Hash, Default, PartialOrd, Ord)]
+pub struct Algorithm(pub Vec<Move>);
+
+impl Algorithm {
+    pub fn new() -> Self {
+        Algorithm(vec![])
+    }
+
+    /// The algorithm that undoes this one.
+    pub fn inverse(&self) -> Algorithm {
+        self.iter().rev().map(|m| m.inverse()).collect()
+    }
+
+    pub fn apply(&self, rc: &mut RubiksCube3x3) {
+        for &m in self.iter() {
+            rc.apply(m);
+        }
+    }
+}
+
+impl Deref for Algorithm {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.0
+    }
+}
+
+impl From<Vec<Move>> for Algorithm {
+    fn from(moves: Vec<Move>) -> Self {
+        Algorithm(moves)
+    }
+}
+
+impl From<&[Move]> for Algorithm {
+    fn from(moves: &[Move]) -> Self {
+        Algorithm(moves.to_vec())
+    }
+}
+
+impl FromIterator<Move> for Algorithm {
+    fn from_iter<I: IntoIterator<Item = Move>>(iter: I) -> Self {
+        Algorithm(iter.into_iter().collect())
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, m) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", m)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseAlgorithmError {
+    /// The text that could not be read as a move.
+    pub token: String,
+    /// Where `token` starts in the input, counted in characters.
+    pub position: usize,
+}
+
+impl fmt::Display for ParseAlgorithmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' at position {} is not a move", self.token, self.position)
+    }
+}
+
+impl std::error::Error for ParseAlgorithmError {}
+
+/// Splits an algorithm into the text of each move and where it starts.
+///
+/// Moves do not need to be separated by spaces: a move starts at a letter
+/// and takes any `w`, digits and primes that follow it, so `RUR'U'` reads
+/// as four moves. Any other character is a token of its own.
+fn tokens(s: &str) -> Vec<(usize, String)> {
+    let mut tokens = vec![];
+    let mut chars = s.chars().enumerate().peekable();
+
+    while let Some((position, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let mut token = String::from(c);
+
+        if c.is_alphabetic() {
+            while let Some(&(_, 'w')) = chars.peek() {
+                token.push('w');
+                chars.next();
+            }
+
+            while let Some(&(_, next)) = chars.peek() {
+                if !(next.is_ascii_digit() || next == '\'' || next == '’') {
+                    break;
+                }
+                token.push(next);
+                chars.next();
+            }
+        }
+
+        tokens.push((position, token));
+    }
+
+    tokens
+}
+
+impl FromStr for Algorithm {
+    type Err = ParseAlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        tokens(s)
+            .into_iter()
+            .map(|(position, token)| token
+                 .parse()
+                 .map_err(|_| ParseAlgorithmError { token, position }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rubiks::RubiksCube;
+
+    #[test]
+    fn parse_reads_standard_notation() {
+        let algorithm: Algorithm = "R U' F2 M E' S2".parse().unwrap();
+
+        assert_eq!(vec![Move::R, Move::UPrime, Move::F2, Move::M, Move::EPrime, Move::S2],
+                   algorithm.0);
+    }
+
+    #[test]
+    fn parse_reads_wide_moves_and_rotations() {
+        let algorithm: Algorithm = "Rw r' x y2 z'".parse().unwrap();
+
+        assert_eq!(vec![Move::Rw, Move::RwPrime, Move::X, Move::Y2, Move::ZPrime],
+                   algorithm.0);
+    }
+
+    #[test]
+    fn parse_does_not_need_spaces() {
+        assert_eq!("R U R' U'".parse::<Algorithm>(), "RUR'U'".parse());
+        assert_eq!("Rw2 U".parse::<Algorithm>(), "Rw2U".parse());
+    }
+
+    #[test]
+    fn parse_accepts_empty_algorithm() {
+        assert_eq!(Ok(Algorithm::new()), "  ".parse());
+    }
+
+    #[test]
+    fn parse_reports_token_and_position() {
+        assert_eq!(Err(ParseAlgorithmError { token: String::from("U3"), position: 2 }),
+                   "R U3 F".parse::<Algorithm>());
+        assert_eq!(Err(ParseAlgorithmError { token: String::from("Q"), position: 4 }),
+                   "R U Q".parse::<Algorithm>());
+        assert_eq!(Err(ParseAlgorithmError { token: String::from("("), position: 0 }),
+                   "(R U)".parse::<Algorithm>());
+    }
+
+    #[test]
+    fn display_separates_moves_with_spaces() {
+        let algorithm = Algorithm(vec![Move::UPrime, Move::RPrime, Move::Fw2, Move::Y]);
+
+        assert_eq!("U' R' Fw2 y", algorithm.to_string());
+        assert_eq!(Ok(algorithm.clone()), algorithm.to_string().parse());
+    }
+
+    #[test]
+    fn inverse_undoes_algorithm() {
+        let algorithm: Algorithm = "R U F2 M' Lw x".parse().unwrap();
+        let mut rc = RubiksCube3x3::new();
+
+        algorithm.apply(&mut rc);
+        assert_eq!(false, rc.solved());
+
+        algorithm.inverse().apply(&mut rc);
+        assert_eq!(true, rc.solved());
+    }
+}
@@ -0,0 +1,483 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+use crate::algorithm::Algorithm;
+use crate::goal::{Goal, LastLayerOnly};
+use crate::moves::*;
+use crate::optimal::upright;
+use crate::pattern::{PatternDatabase, FACE_TURNS};
+use crate::rubiks::*;
+
+/// The stages of the layer by layer method, in the order they are done.
+/// The white face is D and the yellow face U.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash)]
+pub enum Stage {
+    WhiteCross,
+    WhiteCorners,
+    MiddleLayer,
+    YellowCross,
+    YellowCorners,
+    CornerPermutation,
+    EdgePermutation,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 7] = [
+        Stage::WhiteCross, Stage::WhiteCorners, Stage::MiddleLayer,
+        Stage::YellowCross, Stage::YellowCorners, Stage::CornerPermutation, Stage::EdgePermutation,
+    ];
+
+    /// What the stage does, and how, for someone learning it.
+    pub fn description(self) -> &'static str {
+        match self {
+            Stage::WhiteCross =>
+                "Put the four white edges around the white centre, each with its other colour matching the \
+                 centre beside it.",
+            Stage::WhiteCorners =>
+                "Bring each white corner above where it belongs and repeat R U R' U', turned to face its \
+                 slot, until it drops in the right way round.",
+            Stage::MiddleLayer =>
+                "Line each middle edge up with its centre on top, then take it down to the right with \
+                 U R U' R' U' F' U F or to the left with U' L' U L U F U' F'.",
+            Stage::YellowCross =>
+                "Repeat F R U R' U' F', with the right face of the top to the front, until the top edges \
+                 all show yellow.",
+            Stage::YellowCorners =>
+                "Repeat R U R' U R U2 R', turning the top between goes, until the top is all yellow.",
+            Stage::CornerPermutation =>
+                "Cycle the top corners with R' F R' B2 R F' R' B2 R2 until each is between the right \
+                 centres.",
+            Stage::EdgePermutation =>
+                "Cycle the top edges with R U' R U R U R U' R' U' R2, or its inverse, and turn the top \
+                 to finish.",
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Stage::WhiteCross => "white cross",
+            Stage::WhiteCorners => "white corners",
+            Stage::MiddleLayer => "middle layer",
+            Stage::YellowCross => "yellow cross",
+            Stage::YellowCorners => "yellow corners",
+            Stage::CornerPermutation => "corner permutation",
+            Stage::EdgePermutation => "edge permutation",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Part of a stage: the moves that place one piece, or for the last layer,
+/// the whole stage, and the cube they leave.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Step {
+    pub stage: Stage,
+    /// The piece the step puts in place, if it is about one piece.
+    pub piece: Option<Cubie>,
+    pub moves: Algorithm,
+    pub cube: RubiksCube3x3,
+}
+
+/// A solution a stage at a time, after a rotation if slice moves left the
+/// centres out of place.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Solution {
+    pub rotation: Algorithm,
+    pub steps: Vec<Step>,
+}
+
+impl Solution {
+    /// Every move of the solution, one step after another.
+    pub fn algorithm(&self) -> Algorithm {
+        self.rotation
+            .iter()
+            .chain(self.steps.iter().flat_map(|step| step.moves.iter()))
+            .copied()
+            .collect()
+    }
+}
+
+/// The rotation, then each step that makes moves, labelled with its stage
+/// and piece, with what a stage does written out before its first step.
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.rotation.is_empty() {
+            writeln!(f, "rotate: {}", self.rotation)?;
+        }
+
+        let mut described = None;
+        for step in self.steps.iter().filter(|step| !step.moves.is_empty()) {
+            if described != Some(step.stage) {
+                writeln!(f, "{}", step.stage.description())?;
+                described = Some(step.stage);
+            }
+
+            match step.piece {
+                Some(Cubie::Corner(corner)) => writeln!(f, "{} ({:?}): {}", step.stage, corner, step.moves)?,
+                Some(Cubie::Edge(edge)) => writeln!(f, "{} ({:?}): {}", step.stage, edge, step.moves)?,
+                _ => writeln!(f, "{}: {}", step.stage, step.moves)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+const CROSS: [Edge; 4] = [Edge::DF, Edge::DR, Edge::DB, Edge::DL];
+
+/// Each white corner, with the face to its right looking at it from the
+/// front and the top corner above it.
+const CORNER_SLOTS: [(Corner, Layer, Corner); 4] = [
+    (Corner::DFR, Layer::R, Corner::URF),
+    (Corner::DRB, Layer::B, Corner::UBR),
+    (Corner::DBL, Layer::L, Corner::ULB),
+    (Corner::DLF, Layer::F, Corner::UFL),
+];
+
+/// Each middle edge, with the faces to its left and right looking at it
+/// from between them.
+const EDGE_SLOTS: [(Edge, Layer, Layer); 4] = [
+    (Edge::FR, Layer::F, Layer::R),
+    (Edge::BR, Layer::R, Layer::B),
+    (Edge::BL, Layer::B, Layer::L),
+    (Edge::FL, Layer::L, Layer::F),
+];
+
+const YELLOW_CROSS: &str = "F R U R' U' F'";
+const SUNE: &str = "R U R' U R U2 R'";
+const A_PERM: &str = "R' F R' B2 R F' R' B2 R2";
+const U_PERM: &str = "R U' R U R U R U' R' U' R2";
+
+fn algorithm(text: &str) -> Algorithm {
+    text.parse().expect("stage algorithms are written correctly")
+}
+
+fn corner_home(rc: &RubiksCube3x3, corner: Corner) -> bool {
+    rc.corners[corner as usize] == corner && rc.twists[corner as usize] == 0
+}
+
+fn edge_home(rc: &RubiksCube3x3, edge: Edge) -> bool {
+    rc.edges[edge as usize] == edge && rc.flips[edge as usize] == 0
+}
+
+fn applied(rc: &RubiksCube3x3, moves: &Algorithm) -> RubiksCube3x3 {
+    let mut turned = *rc;
+    moves.apply(&mut turned);
+    turned
+}
+
+/// Turns of the top layer, the first doing nothing.
+fn top_turns() -> [Algorithm; 4] {
+    [Algorithm::new(), algorithm("U"), algorithm("U2"), algorithm("U'")]
+}
+
+/// The fewest `algorithms` and top turns, one after another, that take `rc`
+/// to where `goal` holds, or `None` if it takes more than `most` of them.
+fn shortest_with(rc: &RubiksCube3x3,
+                 algorithms: &[Algorithm],
+                 most: usize,
+                 goal: impl Fn(&RubiksCube3x3) -> bool) -> Option<Algorithm> {
+    let steps: Vec<Algorithm> = top_turns().into_iter().skip(1).chain(algorithms.iter().cloned()).collect();
+    let mut seen = HashSet::from([*rc]);
+    let mut queue = VecDeque::from([(*rc, Algorithm::new(), 0)]);
+
+    while let Some((rc, moves, depth)) = queue.pop_front() {
+        if goal(&rc) {
+            return Some(moves);
+        }
+        if depth == most {
+            continue;
+        }
+
+        for step in &steps {
+            let turned = applied(&rc, step);
+            if seen.insert(turned) {
+                let longer: Algorithm = moves.iter().chain(step.iter()).copied().collect();
+                queue.push_back((turned, longer, depth + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Solves a cube the way people learn to, a layer at a time: a cross and
+/// then the corners on the white face, the middle edges, and the yellow
+/// face a few algorithms at a time. Solutions run to a hundred moves or so,
+/// but each step can be followed and explained.
+pub struct BeginnerSolver {
+    cross: PatternDatabase,
+}
+
+impl Default for BeginnerSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BeginnerSolver {
+    pub fn new() -> Self {
+        BeginnerSolver { cross: PatternDatabase::new(&[], &CROSS) }
+    }
+
+    /// The steps that solve `rc`, or `None` if one cannot be done, which
+    /// only happens to cubes that were put together wrong.
+    pub fn solve(&self, rc: &RubiksCube3x3) -> Option<Solution> {
+        let (rotation, mut rc) = upright(rc);
+        let mut steps = vec![];
+        let mut step = |stage: Stage, piece: Option<Cubie>, moves: Algorithm, rc: &mut RubiksCube3x3| {
+            moves.apply(rc);
+            steps.push(Step { stage, piece, moves, cube: *rc });
+        };
+
+        let moves = self.cross(&rc);
+        step(Stage::WhiteCross, None, moves, &mut rc);
+
+        for (corner, right, above) in CORNER_SLOTS {
+            let moves = white_corner(&rc, corner, right, above)?;
+            step(Stage::WhiteCorners, Some(Cubie::Corner(corner)), moves, &mut rc);
+        }
+
+        for (edge, left, right) in EDGE_SLOTS {
+            let moves = middle_edge(&rc, edge, left, right)?;
+            step(Stage::MiddleLayer, Some(Cubie::Edge(edge)), moves, &mut rc);
+        }
+
+        let first_two_layers = |rc: &RubiksCube3x3| LastLayerOnly.matches(rc, 0);
+        let yellow_edges = |rc: &RubiksCube3x3| first_two_layers(rc) && rc.flips.iter().all(|&flip| flip == 0);
+        let yellow_face = |rc: &RubiksCube3x3| yellow_edges(rc) && rc.twists.iter().all(|&twist| twist == 0);
+        let corners_placed = |rc: &RubiksCube3x3| yellow_face(rc) && top_turns()
+            .iter()
+            .any(|turn| applied(rc, turn).corners == Corner::ALL);
+
+        let moves = shortest_with(&rc, &[algorithm(YELLOW_CROSS)], 6, yellow_edges)?;
+        step(Stage::YellowCross, None, moves, &mut rc);
+
+        let moves = shortest_with(&rc, &[algorithm(SUNE)], 8, yellow_face)?;
+        step(Stage::YellowCorners, None, moves, &mut rc);
+
+        let moves = shortest_with(&rc, &[algorithm(A_PERM)], 5, corners_placed)?;
+        step(Stage::CornerPermutation, None, moves, &mut rc);
+
+        let u_perm = algorithm(U_PERM);
+        let moves = shortest_with(&rc, &[u_perm.clone(), u_perm.inverse()], 5, |rc| rc.solved())?;
+        step(Stage::EdgePermutation, None, moves, &mut rc);
+
+        Some(Solution { rotation, steps })
+    }
+
+    /// The fewest face turns that put the white edges in place, as the
+    /// pattern database following just those knows exactly.
+    fn cross(&self, rc: &RubiksCube3x3) -> Algorithm {
+        let mut rc = *rc;
+        let mut moves = vec![];
+
+        while self.cross.distance(&rc) > 0 {
+            let distance = self.cross.distance(&rc);
+            let m = *FACE_TURNS
+                .iter()
+                .find(|&&m| {
+                    let mut turned = rc;
+                    turned.apply(m);
+                    self.cross.distance(&turned) < distance
+                })
+                .expect("some turn gets the cross closer");
+
+            rc.apply(m);
+            moves.push(m);
+        }
+
+        Algorithm(moves)
+    }
+}
+
+/// `moves` followed by `more`, with turns of the same layer where they
+/// meet made into one, as someone following them would do.
+fn extend(moves: &mut Algorithm, more: &Algorithm) {
+    for &m in more.iter() {
+        match moves.0.last() {
+            Some(&last) if last.layer() == m.layer() => {
+                moves.0.pop();
+                let quarters = (last.turn().quarters() + m.turn().quarters()) % 4;
+                if let Some(&turn) = Turn::ALL.iter().find(|turn| turn.quarters() == quarters) {
+                    moves.0.push(Move::new(m.layer(), turn));
+                }
+            }
+            _ => moves.0.push(m),
+        }
+    }
+}
+
+/// `R U R' U'`, with `right` in place of R.
+fn sexy_move(right: Layer) -> Algorithm {
+    algorithm(&format!("{0} U {0}' U'", right))
+}
+
+/// Takes `corner` out of whichever bottom slot it is in, turns the top to
+/// bring it above its own slot, and repeats the slot's `R U R' U'` until it
+/// is in.
+fn white_corner(rc: &RubiksCube3x3, corner: Corner, right: Layer, above: Corner) -> Option<Algorithm> {
+    if corner_home(rc, corner) {
+        return Some(Algorithm::new());
+    }
+
+    let mut moves = Algorithm::new();
+    let position = |rc: &RubiksCube3x3| rc.corners.iter().position(|&c| c == corner).unwrap();
+
+    if let Some(&(_, other_right, _)) = CORNER_SLOTS.iter().find(|&&(slot, _, _)| slot as usize == position(rc)) {
+        extend(&mut moves, &sexy_move(other_right));
+    }
+
+    let turn = top_turns().into_iter().find(|turn| position(&applied(&applied(rc, &moves), turn)) == above as usize)?;
+    extend(&mut moves, &turn);
+
+    let insert = sexy_move(right);
+    for _ in 0..6 {
+        if corner_home(&applied(rc, &moves), corner) {
+            return Some(moves);
+        }
+        extend(&mut moves, &insert);
+    }
+
+    None
+}
+
+/// Takes `edge` out of whichever middle slot it is in, then lines it up
+/// over its centre and takes it down to the right or left, whichever puts
+/// it in the right way round.
+fn middle_edge(rc: &RubiksCube3x3, edge: Edge, left: Layer, right: Layer) -> Option<Algorithm> {
+    if edge_home(rc, edge) {
+        return Some(Algorithm::new());
+    }
+
+    // Down to the right, looking at the slot from `left`, and down to the
+    // left, looking at it from `right`.
+    let inserts = |left: Layer, right: Layer| [
+        algorithm(&format!("U {1} U' {1}' U' {0}' U {0}", left, right)),
+        algorithm(&format!("U' {0}' U {0} U {1} U' {1}'", left, right)),
+    ];
+
+    let mut extracted = Algorithm::new();
+    let position = rc.edges.iter().position(|&e| e == edge).unwrap();
+
+    if let Some(&(_, other_left, other_right)) = EDGE_SLOTS.iter().find(|&&(slot, _, _)| slot as usize == position) {
+        extend(&mut extracted, &inserts(other_left, other_right)[0]);
+    }
+
+    top_turns()
+        .into_iter()
+        .flat_map(|turn| inserts(left, right).map(|insert| {
+            let mut moves = extracted.clone();
+            extend(&mut moves, &turn);
+            extend(&mut moves, &insert);
+            moves
+        }))
+        .find(|moves| edge_home(&applied(rc, moves), edge))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use super::*;
+
+    fn solver() -> &'static BeginnerSolver {
+        static SOLVER: OnceLock<BeginnerSolver> = OnceLock::new();
+        SOLVER.get_or_init(BeginnerSolver::new)
+    }
+
+    fn scrambled(algorithm: &str) -> RubiksCube3x3 {
+        let mut rc = RubiksCube3x3::new();
+        algorithm.parse::<Algorithm>().unwrap().apply(&mut rc);
+        rc
+    }
+
+    const SCRAMBLES: [&str; 5] = [
+        "",
+        "R U R' U'",
+        "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2",
+        "F R2 D' B L U2 R' F' D B2 L' U F2 D R L2 B' U' R F",
+        "D2 B' L U' F2 R D' L2 B U R' F D2 L' B2 U' F R2 D M S",
+    ];
+
+    #[test]
+    fn solutions_solve() {
+        for scramble in SCRAMBLES {
+            let mut rc = scrambled(scramble);
+            let solution = solver().solve(&rc).unwrap();
+            solution.algorithm().apply(&mut rc);
+
+            assert!(rc.solved(), "{} does not solve {}", solution.algorithm(), scramble);
+        }
+    }
+
+    #[test]
+    fn each_step_keeps_what_earlier_steps_solved() {
+        for scramble in SCRAMBLES {
+            let solution = solver().solve(&scrambled(scramble)).unwrap();
+            let mut placed: Vec<Cubie> = vec![];
+
+            for step in &solution.steps {
+                if step.stage == Stage::WhiteCross {
+                    assert!(CROSS.iter().all(|&edge| edge_home(&step.cube, edge)));
+                    placed.extend(CROSS.map(Cubie::Edge));
+                }
+                placed.extend(step.piece);
+
+                for piece in &placed {
+                    match *piece {
+                        Cubie::Corner(corner) => assert!(corner_home(&step.cube, corner), "{:?} after {}", piece, step.stage),
+                        Cubie::Edge(edge) => assert!(edge_home(&step.cube, edge), "{:?} after {}", piece, step.stage),
+                        Cubie::Centre(_) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn steps_come_in_stage_order() {
+        let solution = solver().solve(&scrambled(SCRAMBLES[3])).unwrap();
+        let stages: Vec<Stage> = solution.steps.iter().map(|step| step.stage).collect();
+
+        assert!(stages.is_sorted());
+        assert_eq!(1 + 4 + 4 + 4, stages.len());
+        assert_eq!(Stage::ALL.to_vec(), { let mut unique = stages; unique.dedup(); unique });
+    }
+
+    #[test]
+    fn display_explains_each_stage_before_its_first_step() {
+        let solution = solver().solve(&scrambled(SCRAMBLES[3])).unwrap();
+        let text = solution.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+
+        for stage in Stage::ALL {
+            let description = lines.iter().position(|&line| line == stage.description());
+            let first_step = lines.iter().position(|line| line.starts_with(&stage.to_string()));
+
+            assert_eq!(1, lines.iter().filter(|&&line| line == stage.description()).count(), "{}", stage);
+            assert_eq!(first_step.map(|n| n - 1), description, "{}", stage);
+        }
+    }
+
+    #[test]
+    fn extend_joins_turns_of_the_same_layer() {
+        let mut moves = algorithm("R U");
+        extend(&mut moves, &algorithm("U R"));
+        assert_eq!("R U2 R", moves.to_string());
+
+        extend(&mut moves, &algorithm("R' U2 F"));
+        assert_eq!("R F", moves.to_string());
+    }
+
+    #[test]
+    fn white_corners_go_in_with_repeated_sexy_moves() {
+        let solve = |scramble| white_corner(&scrambled(scramble), Corner::DFR, Layer::R, Corner::URF).unwrap();
+
+        assert_eq!("R U R' U'", solve("U R U' R'").to_string());
+        assert_eq!(format!("U' {}", ["R U R' U'"; 5].join(" ")), solve("R U R'").to_string());
+    }
+}
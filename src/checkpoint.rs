@@ -0,0 +1,212 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::algorithm::Algorithm;
+use crate::equivalence::{Class, EquivalenceClasses};
+use crate::moves::{Metric, Move};
+
+/// How far a search of one depth has got: which prefixes have been searched
+/// all the way down, and the classes found under them.
+///
+/// It is saved as text, one item per line:
+///
+/// ```text
+/// depth 7
+/// pool U U' R R'
+/// metric stm
+/// prefix-length 2
+/// goal order>=60
+/// done U R
+/// class 105 48 U R
+/// ```
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    pub depth: usize,
+    /// The moves searched over, which a resumed search has to use as well.
+    pub pool: Vec<Move>,
+    /// How depth is counted, which a resumed search has to use as well.
+    pub metric: Metric,
+    /// How many moves, counted in `metric`, the search was split by.
+    pub prefix_length: usize,
+    /// What the search was keeping, as given to `parse_goal`, if it was not
+    /// keeping everything. A resumed search has to keep the same.
+    pub goal: Option<String>,
+    pub done: Vec<Vec<Move>>,
+    pub classes: EquivalenceClasses,
+}
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    /// Line `line`, counting from 1, could not be read.
+    Parse { line: usize, text: String },
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckpointError::Io(error) => write!(f, "{}", error),
+            CheckpointError::Parse { line, text } =>
+                write!(f, "cannot read line {} of checkpoint: '{}'", line, text),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<io::Error> for CheckpointError {
+    fn from(error: io::Error) -> Self {
+        CheckpointError::Io(error)
+    }
+}
+
+impl Checkpoint {
+    /// A checkpoint from before anything at `depth` has been searched.
+    pub fn new(depth: usize, pool: &[Move], metric: Metric, prefix_length: usize) -> Self {
+        Checkpoint {
+            depth,
+            pool: pool.to_vec(),
+            metric,
+            prefix_length,
+            goal: None,
+            done: vec![],
+            classes: EquivalenceClasses::new(),
+        }
+    }
+
+    /// Writes the checkpoint to `path`, replacing it only once the new one is
+    /// complete, so a crash part way through still leaves the old one.
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        fs::write(&partial, self.to_string())?;
+        fs::rename(&partial, path)?;
+
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CheckpointError> {
+        fs::read_to_string(path)?.parse()
+    }
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "depth {}", self.depth)?;
+        writeln!(f, "pool {}", Algorithm::from(self.pool.as_slice()))?;
+        writeln!(f, "metric {}", self.metric)?;
+        writeln!(f, "prefix-length {}", self.prefix_length)?;
+
+        if let Some(goal) = &self.goal {
+            writeln!(f, "goal {}", goal)?;
+        }
+
+        for prefix in &self.done {
+            writeln!(f, "done {}", Algorithm::from(prefix.as_slice()))?;
+        }
+
+        for class in self.classes.classes() {
+            writeln!(f, "class {} {} {}", class.order, class.size, class.representative)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Checkpoint {
+    type Err = CheckpointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut checkpoint = Checkpoint::new(0, &[], Metric::Stm, 0);
+
+        for (index, text) in s.lines().enumerate() {
+            let error = || CheckpointError::Parse { line: index + 1, text: text.to_string() };
+
+            let (name, value) = text.split_once(' ').unwrap_or((text, ""));
+
+            match name {
+                "depth" => checkpoint.depth = value.parse().map_err(|_| error())?,
+                "pool" => checkpoint.pool = value.parse::<Algorithm>().map_err(|_| error())?.0,
+                "metric" => checkpoint.metric = value.parse().map_err(|_| error())?,
+                "prefix-length" => checkpoint.prefix_length = value.parse().map_err(|_| error())?,
+                "goal" => checkpoint.goal = Some(value.to_string()),
+                "done" => checkpoint.done.push(value.parse::<Algorithm>().map_err(|_| error())?.0),
+                "class" => {
+                    let mut fields = value.splitn(3, ' ');
+                    let mut field = || fields.next().ok_or_else(error);
+
+                    let order = field()?.parse().map_err(|_| error())?;
+                    let size = field()?.parse().map_err(|_| error())?;
+                    let representative = field()?.parse().map_err(|_| error())?;
+
+                    checkpoint.classes.insert(Class { representative, order, size });
+                }
+                "" => {}
+                _ => return Err(error()),
+            }
+        }
+
+        Ok(checkpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Checkpoint {
+        let mut checkpoint = Checkpoint::new(3, &[Move::U, Move::UPrime, Move::R, Move::RPrime], Metric::Qtm, 1);
+        checkpoint.goal = Some(String::from("order>=4, preserves-corners"));
+        checkpoint.done.push(vec![Move::U]);
+        checkpoint.done.push(vec![Move::UPrime]);
+        checkpoint.classes.add(&[Move::U, Move::R, Move::U], 36);
+        checkpoint.classes.add(&[Move::U, Move::U, Move::R], 36);
+        checkpoint.classes.add(&[Move::U, Move::R, Move::RPrime], 4);
+        checkpoint
+    }
+
+    #[test]
+    fn display_then_parse_gives_same_checkpoint() {
+        let checkpoint = example();
+        let parsed: Checkpoint = checkpoint.to_string().parse().unwrap();
+
+        assert_eq!(checkpoint.depth, parsed.depth);
+        assert_eq!(checkpoint.pool, parsed.pool);
+        assert_eq!(checkpoint.metric, parsed.metric);
+        assert_eq!(checkpoint.prefix_length, parsed.prefix_length);
+        assert_eq!(checkpoint.goal, parsed.goal);
+        assert_eq!(checkpoint.done, parsed.done);
+        assert_eq!(checkpoint.classes.classes().collect::<Vec<_>>(),
+                   parsed.classes.classes().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn save_then_load_gives_same_checkpoint() {
+        let path = std::env::temp_dir().join(format!("checkpoint-test-{}", std::process::id()));
+        let checkpoint = example();
+
+        checkpoint.save(&path).unwrap();
+        let loaded = Checkpoint::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(checkpoint.to_string(), loaded.to_string());
+    }
+
+    #[test]
+    fn parse_reports_line_it_cannot_read() {
+        let error = "depth 3\npool R U\nclass 105 many R U".parse::<Checkpoint>().unwrap_err();
+
+        assert!(matches!(error, CheckpointError::Parse { line: 3, .. }), "{:?}", error);
+    }
+
+    #[test]
+    fn load_reports_missing_file() {
+        let error = Checkpoint::load(Path::new("/nonexistent/checkpoint")).unwrap_err();
+
+        assert!(matches!(error, CheckpointError::Io(_)));
+    }
+}
@@ -0,0 +1,357 @@
+use std::fmt;
+
+use crate::rubiks::*;
+
+/// Pieces that move into each other's places: the cubie starting at
+/// `positions[0]` moves to `positions[1]`, and so on around the cycle.
+/// `twist` is how far each piece is turned, in its own orientation steps,
+/// once it has gone all the way round and is back where it started.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Cycle<T> {
+    pub positions: Vec<T>,
+    pub twist: u8,
+}
+
+impl<T> Cycle<T> {
+    /// How many times the cycle has to be repeated to put its pieces back,
+    /// oriented, where they started. `modulus` is the number of
+    /// orientations a piece has.
+    pub fn order(&self, modulus: u8) -> u64 {
+        cycle_order(self.positions.len(), self.twist, modulus)
+    }
+}
+
+/// The shape of a set of cycles, leaving out which pieces are in them: the
+/// length and twist of each cycle, longest first.
+///
+/// It is written with the lengths separated by spaces and a twist of 1
+/// marked `+` and 2 marked `-`, so the corners of `R U` are `5- 1+`, and a
+/// flipped edge standing still is `1+`.
+#[derive(PartialEq, Eq, Clone, Debug, Hash, PartialOrd, Ord, Default)]
+pub struct CycleType(pub Vec<(usize, u8)>);
+
+impl CycleType {
+    pub fn of<T>(cycles: &[Cycle<T>]) -> Self {
+        let mut shape: Vec<(usize, u8)> = cycles
+            .iter()
+            .map(|cycle| (cycle.positions.len(), cycle.twist))
+            .collect();
+        shape.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        CycleType(shape)
+    }
+}
+
+impl fmt::Display for CycleType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, &(length, twist)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+
+            let mark = match twist {
+                0 => "",
+                1 => "+",
+                _ => "-",
+            };
+            write!(f, "{}{}", length, mark)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn cycle_order(length: usize, twist: u8, modulus: u8) -> u64 {
+    let length = length as u64;
+    let modulus = modulus as u64;
+
+    length * modulus / gcd(twist as u64 % modulus, modulus)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+pub(crate) fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+/// Walks every cycle of a permutation, stored as the piece sitting in each
+/// position, calling `visit` with the positions in the order pieces move
+/// through them and the net twist picked up on the way round.
+fn for_each_cycle<const N: usize>(pieces: [usize; N],
+                                  twists: &[u8; N],
+                                  modulus: u8,
+                                  mut visit: impl FnMut(&[usize], u8)) {
+    let mut destination = [0; N];
+    for (position, &piece) in pieces.iter().enumerate() {
+        destination[piece] = position;
+    }
+
+    let mut visited = [false; N];
+    let mut positions = [0; N];
+
+    for start in 0..N {
+        let mut length = 0;
+        let mut twist = 0;
+        let mut position = start;
+
+        while !visited[position] {
+            visited[position] = true;
+            positions[length] = position;
+            length += 1;
+            twist = (twist + twists[position]) % modulus;
+            position = destination[position];
+        }
+
+        if length > 0 {
+            visit(&positions[..length], twist);
+        }
+    }
+}
+
+/// The cycles of a permutation, leaving out pieces that stay put untwisted.
+fn cycles<T: Copy, const N: usize>(pieces: [usize; N],
+                                   twists: &[u8; N],
+                                   all: &[T; N],
+                                   modulus: u8) -> Vec<Cycle<T>> {
+    let mut result = vec![];
+
+    for_each_cycle(pieces, twists, modulus, |positions, twist| {
+        if positions.len() > 1 || twist != 0 {
+            result.push(Cycle {
+                positions: positions.iter().map(|&position| all[position]).collect(),
+                twist,
+            });
+        }
+    });
+
+    result
+}
+
+pub fn corner_cycles(rc: &RubiksCube3x3) -> Vec<Cycle<Corner>> {
+    cycles(rc.corners.map(|c| c as usize), &rc.twists, &Corner::ALL, 3)
+}
+
+pub fn edge_cycles(rc: &RubiksCube3x3) -> Vec<Cycle<Edge>> {
+    cycles(rc.edges.map(|e| e as usize), &rc.flips, &Edge::ALL, 2)
+}
+
+/// Centres have no visible orientation, so their cycles never twist.
+pub fn centre_cycles(rc: &RubiksCube3x3) -> Vec<Cycle<Face>> {
+    cycles(rc.centres.map(|f| f as usize), &[0; 6], &Face::ALL, 1)
+}
+
+/// The number of times the moves that made `rc` have to be repeated, from
+/// solved, before the cube is solved again: the least common multiple of
+/// the orders of its cycles.
+pub fn order(rc: &RubiksCube3x3) -> u64 {
+    let mut order = 1;
+
+    for_each_cycle(rc.corners.map(|c| c as usize), &rc.twists, 3, |positions, twist| {
+        order = lcm(order, cycle_order(positions.len(), twist, 3));
+    });
+    for_each_cycle(rc.edges.map(|e| e as usize), &rc.flips, 2, |positions, twist| {
+        order = lcm(order, cycle_order(positions.len(), twist, 2));
+    });
+    for_each_cycle(rc.centres.map(|f| f as usize), &[0; 6], 1, |positions, _| {
+        order = lcm(order, positions.len() as u64);
+    });
+
+    order
+}
+
+/// Every cycle a move sequence makes, and the order they add up to: why
+/// the sequence takes as many repetitions as it does.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CycleStructure {
+    pub corners: Vec<Cycle<Corner>>,
+    pub edges: Vec<Cycle<Edge>>,
+    /// Only slices, wide moves and rotations move centres.
+    pub centres: Vec<Cycle<Face>>,
+    pub order: u64,
+}
+
+impl CycleStructure {
+    pub fn of(rc: &RubiksCube3x3) -> Self {
+        CycleStructure {
+            corners: corner_cycles(rc),
+            edges: edge_cycles(rc),
+            centres: centre_cycles(rc),
+            order: order(rc),
+        }
+    }
+
+    /// The orders of the cycles, without repeats, smallest first.
+    pub fn cycle_orders(&self) -> Vec<u64> {
+        let mut orders: Vec<u64> = self.corners.iter().map(|cycle| cycle.order(3))
+            .chain(self.edges.iter().map(|cycle| cycle.order(2)))
+            .chain(self.centres.iter().map(|cycle| cycle.order(1)))
+            .collect();
+        orders.sort();
+        orders.dedup();
+        orders
+    }
+}
+
+/// Writes one kind of piece's cycles, one per line, following the pieces
+/// round: `UR -> UF -> UL (flipped): order 6`.
+fn write_cycles<T: fmt::Debug>(f: &mut fmt::Formatter,
+                               name: &str,
+                               cycles: &[Cycle<T>],
+                               modulus: u8) -> fmt::Result {
+    writeln!(f, "{}", name)?;
+
+    if cycles.is_empty() {
+        return writeln!(f, "  none move");
+    }
+
+    for cycle in cycles {
+        let positions: Vec<String> = cycle.positions.iter().map(|p| format!("{:?}", p)).collect();
+        write!(f, "  {}", positions.join(" -> "))?;
+
+        match (modulus, cycle.twist) {
+            (_, 0) => {}
+            (2, _) => write!(f, " (flipped)")?,
+            (_, twist) => write!(f, " (twisted {})", if twist == 1 { "clockwise" } else { "anticlockwise" })?,
+        }
+
+        writeln!(f, ": order {}", cycle.order(modulus))?;
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for CycleStructure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_cycles(f, "corners", &self.corners, 3)?;
+        write_cycles(f, "edges", &self.edges, 2)?;
+        write_cycles(f, "centres", &self.centres, 1)?;
+
+        let orders: Vec<String> = self.cycle_orders().iter().map(u64::to_string).collect();
+        match orders.len() {
+            0 | 1 => writeln!(f, "order {}", self.order),
+            _ => writeln!(f, "order {} = lcm({})", self.order, orders.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::Algorithm;
+
+    fn order_of(algorithm: &str) -> u64 {
+        let mut rc = RubiksCube3x3::new();
+        algorithm.parse::<Algorithm>().unwrap().apply(&mut rc);
+
+        order(&rc)
+    }
+
+    fn repetitions_until_solved(algorithm: &str) -> u64 {
+        let algorithm: Algorithm = algorithm.parse().unwrap();
+        let mut rc = RubiksCube3x3::new();
+        let mut count = 0;
+
+        loop {
+            algorithm.apply(&mut rc);
+            count += 1;
+
+            if rc.solved() {
+                return count;
+            }
+        }
+    }
+
+    #[test]
+    fn solved_cube_has_order_one() {
+        assert_eq!(1, order(&RubiksCube3x3::new()));
+    }
+
+    #[test]
+    fn single_moves_have_order_four_or_two() {
+        assert_eq!(4, order_of("R"));
+        assert_eq!(2, order_of("U2"));
+        assert_eq!(4, order_of("M'"));
+        assert_eq!(4, order_of("x"));
+    }
+
+    #[test]
+    fn order_counts_corner_twist_and_edge_flip() {
+        assert_eq!(105, order_of("R U"));
+        assert_eq!(63, order_of("R U'"));
+        assert_eq!(6, order_of("R U R' U'"));
+        assert_eq!(6, order_of("R U R' U R U2 R'"));
+    }
+
+    #[test]
+    fn superflip_is_a_single_flip_in_every_edge() {
+        let mut rc = RubiksCube3x3::new();
+        "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2"
+            .parse::<Algorithm>().unwrap().apply(&mut rc);
+
+        let edges = edge_cycles(&rc);
+
+        assert_eq!(12, edges.len());
+        assert!(edges.iter().all(|cycle| cycle.positions.len() == 1 && cycle.twist == 1));
+        assert!(corner_cycles(&rc).is_empty());
+        assert_eq!(2, order(&rc));
+    }
+
+    #[test]
+    fn cycles_follow_pieces_in_the_direction_they_move() {
+        let mut rc = RubiksCube3x3::new();
+        rc.apply(crate::moves::Move::U);
+
+        assert_eq!(vec![Cycle {
+            positions: vec![Corner::URF, Corner::UFL, Corner::ULB, Corner::UBR],
+            twist: 0,
+        }], corner_cycles(&rc));
+    }
+
+    #[test]
+    fn cycle_type_lists_longest_cycles_first() {
+        let mut rc = RubiksCube3x3::new();
+        "R U".parse::<Algorithm>().unwrap().apply(&mut rc);
+
+        assert_eq!(CycleType(vec![(5, 2), (1, 1)]), CycleType::of(&corner_cycles(&rc)));
+        assert_eq!("5- 1+", CycleType::of(&corner_cycles(&rc)).to_string());
+        assert_eq!("7", CycleType::of(&edge_cycles(&rc)).to_string());
+    }
+
+    #[test]
+    fn cycle_structure_explains_order() {
+        let mut rc = RubiksCube3x3::new();
+        "R U R' U'".parse::<Algorithm>().unwrap().apply(&mut rc);
+
+        assert_eq!("\
+corners
+  URF -> DFR (twisted clockwise): order 6
+  ULB -> UBR (twisted anticlockwise): order 6
+edges
+  UR -> UB -> FR: order 3
+centres
+  none move
+order 6 = lcm(3, 6)
+", CycleStructure::of(&rc).to_string());
+    }
+
+    #[test]
+    fn cycle_structure_shows_centres_moved_by_slices() {
+        let mut rc = RubiksCube3x3::new();
+        rc.apply(crate::moves::Move::M);
+
+        let structure = CycleStructure::of(&rc);
+
+        assert_eq!(1, structure.centres.len());
+        assert_eq!(4, structure.centres[0].positions.len());
+        assert!(structure.to_string().contains("centres\n  U -> F -> D -> B: order 4\n"));
+    }
+
+    #[test]
+    fn order_matches_repeating_until_solved() {
+        for algorithm in ["R U F", "R2 D' B M", "L F' E S2 U", "R U2 D' B D'", "Rw U x M2 D"] {
+            assert_eq!(repetitions_until_solved(algorithm), order_of(algorithm), "{}", algorithm);
+        }
+    }
+}
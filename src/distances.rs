@@ -0,0 +1,277 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::moves::*;
+use crate::rubiks2x2::*;
+
+/// Marks an entry the breadth first search has not reached yet.
+const UNSEEN: u8 = 0xF;
+
+/// Marks the start of a saved distance table, and which version it is.
+const MAGIC: &[u8; 4] = b"RC2D";
+
+/// The fewest moves that solve every position of the 2x2x2 cube, counted in
+/// one metric and found by a breadth first search over all `STATES` of
+/// them, kept four bits to a position.
+///
+/// With no slices to turn, the slice turn metric counts the same as the half
+/// turn metric. In the quarter turn metric, where half turns count two, the
+/// search only needs quarter turns.
+pub struct DistanceTable {
+    metric: Metric,
+    distances: Vec<u8>,
+}
+
+impl DistanceTable {
+    /// Works out the distance of every position, which takes some seconds.
+    pub fn new(metric: Metric) -> Self {
+        let mut table = DistanceTable::empty(metric);
+        table.search();
+        table
+    }
+
+    fn empty(metric: Metric) -> Self {
+        DistanceTable { metric, distances: vec![UNSEEN << 4 | UNSEEN; STATES.div_ceil(2)] }
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    pub fn len(&self) -> usize {
+        STATES
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, index: usize) -> u8 {
+        (self.distances[index / 2] >> (index % 2 * 4)) & 0xF
+    }
+
+    fn set(&mut self, index: usize, distance: u8) {
+        let shift = index % 2 * 4;
+        let byte = &mut self.distances[index / 2];
+        *byte = (*byte & !(0xF << shift)) | distance << shift;
+    }
+
+    /// The turns the search goes by, each costing one move.
+    fn turns(&self) -> Vec<Move> {
+        FIXED_CORNER_TURNS
+            .into_iter()
+            .filter(|&m| self.metric.cost(m) == 1)
+            .collect()
+    }
+
+    /// Fills in every entry a depth at a time, expanding the entries found
+    /// at the last depth. Positions are moved by table: the arrangement and
+    /// the twist parts of the index each move on their own.
+    fn search(&mut self) {
+        let turns = self.turns();
+        let moved = |part: usize, size: usize, index: fn(&RubiksCube2x2) -> usize| -> Vec<Vec<usize>> {
+            (0..size)
+                .map(|coordinate| {
+                    let rc = RubiksCube2x2::from_index(part * coordinate);
+                    turns.iter().map(|&m| {
+                        let mut turned = rc;
+                        turned.apply(m);
+                        index(&turned)
+                    }).collect()
+                })
+                .collect()
+        };
+        let arrangements = moved(729, STATES / 729, |rc| rc.index() / 729);
+        let twists = moved(1, 729, |rc| rc.index() % 729);
+
+        self.set(RubiksCube2x2::new().index(), 0);
+
+        let mut found = 1;
+        let mut depth = 0;
+
+        while found > 0 {
+            found = 0;
+
+            for index in 0..self.len() {
+                if self.get(index) != depth {
+                    continue;
+                }
+
+                let (arrangement, twist) = (index / 729, index % 729);
+
+                for m in 0..turns.len() {
+                    let next = arrangements[arrangement][m] * 729 + twists[twist][m];
+                    if self.get(next) == UNSEEN {
+                        self.set(next, depth + 1);
+                        found += 1;
+                    }
+                }
+            }
+
+            depth += 1;
+        }
+    }
+
+    /// The fewest moves that solve `rc`, turning it as a whole for free.
+    pub fn distance(&self, rc: &RubiksCube2x2) -> u8 {
+        self.get(rc.upright().index())
+    }
+
+    /// How many positions are each number of moves from solved, from none
+    /// up to the most any position needs, God's number for the metric.
+    pub fn distribution(&self) -> Vec<usize> {
+        let mut counts = vec![];
+
+        for index in 0..self.len() {
+            let distance = self.get(index) as usize;
+            if counts.len() <= distance {
+                counts.resize(distance + 1, 0);
+            }
+            counts[distance] += 1;
+        }
+
+        counts
+    }
+
+    /// Writes the table to `path`: `MAGIC`, the metric's position in
+    /// `Metric::ALL`, then the distances. It is written alongside first and
+    /// renamed, so a run stopped partway never leaves half a table.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(Metric::ALL.iter().position(|&metric| metric == self.metric).unwrap() as u8);
+        bytes.extend(&self.distances);
+
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, path)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+        let bytes = fs::read(path)?;
+        let rest = bytes.strip_prefix(MAGIC).ok_or_else(|| invalid("not a distance table"))?;
+        let (&metric, rest) = rest.split_first().ok_or_else(|| invalid("distance table cut short"))?;
+        let metric = *Metric::ALL.get(metric as usize).ok_or_else(|| invalid("not a metric"))?;
+
+        let mut table = DistanceTable::empty(metric);
+        if rest.len() != table.distances.len() {
+            return Err(invalid("distance table is the wrong size"));
+        }
+        table.distances.copy_from_slice(rest);
+
+        Ok(table)
+    }
+
+    /// Loads the table saved at `path`, or works it out and saves it there if
+    /// there is none yet. A table saved for another metric is an error.
+    pub fn load_or_new(path: &Path, metric: Metric) -> io::Result<Self> {
+        match DistanceTable::load(path) {
+            Ok(table) if table.metric == metric => Ok(table),
+            Ok(table) => Err(io::Error::new(io::ErrorKind::InvalidData,
+                                            format!("distance table is for {}, not {}", table.metric, metric))),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                let table = DistanceTable::new(metric);
+                table.save(path)?;
+                Ok(table)
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::sync::OnceLock;
+
+    use crate::algorithm::Algorithm;
+    use crate::rubiks::RubiksCube;
+    use crate::search::CanonicalSequences;
+
+    use super::*;
+
+    fn table(metric: Metric) -> &'static DistanceTable {
+        static HTM: OnceLock<DistanceTable> = OnceLock::new();
+        static QTM: OnceLock<DistanceTable> = OnceLock::new();
+
+        match metric {
+            Metric::Qtm => QTM.get_or_init(|| DistanceTable::new(Metric::Qtm)),
+            _ => HTM.get_or_init(|| DistanceTable::new(Metric::Htm)),
+        }
+    }
+
+    fn scrambled(algorithm: &str) -> RubiksCube2x2 {
+        let mut rc = RubiksCube2x2::new();
+        algorithm.parse::<Algorithm>().unwrap().iter().for_each(|&m| rc.apply(m));
+        rc
+    }
+
+    #[test]
+    fn distribution_matches_known_counts() {
+        assert_eq!(vec![1, 9, 54, 321, 1847, 9992, 50136, 227536, 870072, 1887748, 623800, 2644],
+                   table(Metric::Htm).distribution());
+        assert_eq!(vec![1, 6, 27, 120, 534, 2256, 8969, 33058, 114149, 360508, 930588, 1350852, 782536, 90280, 276],
+                   table(Metric::Qtm).distribution());
+    }
+
+    #[test]
+    fn distances_count_moves_in_the_metric() {
+        assert_eq!(0, table(Metric::Htm).distance(&scrambled("x R L'")));
+        assert_eq!(1, table(Metric::Htm).distance(&scrambled("L2")));
+        assert_eq!(2, table(Metric::Qtm).distance(&scrambled("L2")));
+        assert_eq!(3, table(Metric::Htm).distance(&scrambled("R U F")));
+        assert_eq!(1, table(Metric::Htm).distance(&scrambled("R U D'")));
+    }
+
+    #[test]
+    fn canonical_sequences_reach_positions_in_their_distance() {
+        let sequences = CanonicalSequences::new(&FIXED_CORNER_TURNS);
+        let table = table(Metric::Htm);
+
+        for depth in 0..=4 {
+            sequences.for_each(depth, |sequence| {
+                let mut rc = RubiksCube2x2::new();
+                sequence.iter().for_each(|&m| rc.apply(m));
+
+                assert!(table.distance(&rc) as usize <= depth);
+            });
+        }
+    }
+
+    #[test]
+    fn farthest_positions_are_solved_a_move_closer_at_a_time() {
+        let table = table(Metric::Htm);
+        let farthest = (0..STATES).find(|&index| table.get(index) == 11).unwrap();
+        let mut rc = RubiksCube2x2::from_index(farthest);
+
+        for distance in (0..11).rev() {
+            rc = FIXED_CORNER_TURNS
+                .iter()
+                .map(|&m| {
+                    let mut turned = rc;
+                    turned.apply(m);
+                    turned
+                })
+                .find(|turned| table.distance(turned) == distance)
+                .unwrap();
+        }
+        assert!(rc.solved());
+    }
+
+    #[test]
+    fn save_then_load_gives_same_distances() {
+        let path = env::temp_dir().join(format!("distances-{}.dt", std::process::id()));
+        table(Metric::Qtm).save(&path).unwrap();
+
+        let loaded = DistanceTable::load(&path).unwrap();
+        assert_eq!(Metric::Qtm, loaded.metric());
+        assert!(loaded.distances == table(Metric::Qtm).distances);
+        assert!(DistanceTable::load_or_new(&path, Metric::Htm).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+
+use crate::algorithm::Algorithm;
+use crate::moves::*;
+use crate::symmetry::Symmetry;
+
+/// A group of move sequences that all cycle the cube the same way.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Class {
+    /// The member that comes first in move order.
+    pub representative: Algorithm,
+    pub order: u64,
+    /// How many of the sequences added belong to the class.
+    pub size: usize,
+}
+
+/// Groups move sequences that are the same cycle seen differently: started
+/// part way through (`R U F` and `U F R`), run backwards (`R U` and `U' R'`),
+/// or done on a cube that is held another way or mirrored (`R U` and
+/// `L' U'`). Sequences in one class always have the same order.
+#[derive(Clone, Debug)]
+pub struct EquivalenceClasses {
+    images: Vec<[Move; 54]>,
+    /// Whether sequences started part way through share a class.
+    rotations: bool,
+    classes: BTreeMap<Vec<Move>, Class>,
+}
+
+impl EquivalenceClasses {
+    pub fn new() -> Self {
+        let images = Symmetry::all()
+            .into_iter()
+            .map(|symmetry| Move::ALL.map(|m| symmetry.apply(m)))
+            .collect();
+
+        EquivalenceClasses { images, rotations: true, classes: BTreeMap::new() }
+    }
+
+    /// Groups sequences run backwards or done on a cube held another way,
+    /// but not those started part way through. Those are the same cycle
+    /// seen from a cube the first moves have turned, which can take a
+    /// different number of repetitions to look solved.
+    pub fn unrotated() -> Self {
+        EquivalenceClasses { rotations: false, ..EquivalenceClasses::new() }
+    }
+
+    /// No classes yet, grouping sequences the way `self` does.
+    pub fn emptied(&self) -> Self {
+        EquivalenceClasses { images: self.images.clone(), rotations: self.rotations, classes: BTreeMap::new() }
+    }
+
+    /// The same for every sequence in a class: the smallest, in move order,
+    /// of its rotations, those of its inverse and their symmetric images.
+    /// Rotations are left out if the classes are `unrotated`.
+    pub fn key(&self, sequence: &[Move]) -> Vec<Move> {
+        let inverse: Vec<Move> = sequence.iter().rev().map(|m| m.inverse()).collect();
+        let mut best = sequence.to_vec();
+        let mut image = Vec::with_capacity(sequence.len());
+
+        for table in &self.images {
+            for moves in [sequence, &inverse] {
+                image.clear();
+                image.extend(moves.iter().map(|&m| table[m as usize]));
+
+                let starts = if self.rotations { image.len() } else { image.len().min(1) };
+
+                for start in 0..starts {
+                    let rotated = image[start..].iter().chain(&image[..start]);
+
+                    if rotated.clone().lt(best.iter()) {
+                        best.clear();
+                        best.extend(rotated);
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Counts `sequence`, which takes `order` repetitions to solve the cube,
+    /// towards its class.
+    pub fn add(&mut self, sequence: &[Move], order: u64) {
+        let class = self.classes.entry(self.key(sequence)).or_insert_with(|| Class {
+            representative: Algorithm::from(sequence),
+            order,
+            size: 0,
+        });
+
+        if *sequence < *class.representative {
+            class.representative = Algorithm::from(sequence);
+        }
+        class.size += 1;
+    }
+
+    /// Adds in the classes counted by `other`, so that sequences can be
+    /// counted separately, e.g. on different threads, and put together
+    /// after. The result does not depend on the order they are merged in.
+    pub fn merge(&mut self, other: EquivalenceClasses) {
+        for (key, class) in other.classes {
+            self.merge_class(key, class);
+        }
+    }
+
+    /// Adds in a whole class counted elsewhere, e.g. one read back from a
+    /// checkpoint.
+    pub fn insert(&mut self, class: Class) {
+        self.merge_class(self.key(&class.representative), class);
+    }
+
+    fn merge_class(&mut self, key: Vec<Move>, theirs: Class) {
+        match self.classes.get_mut(&key) {
+            Some(ours) => {
+                if theirs.representative < ours.representative {
+                    ours.representative = theirs.representative;
+                }
+                ours.size += theirs.size;
+            }
+            None => {
+                self.classes.insert(key, theirs);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// The classes, in the order of their keys.
+    pub fn classes(&self) -> impl Iterator<Item = &Class> {
+        self.classes.values()
+    }
+
+    /// The `top` classes with the longest cycles, longest first. Classes with
+    /// the same order come in the order of their representatives.
+    pub fn longest(&self, top: usize) -> Vec<&Class> {
+        let mut classes: Vec<&Class> = self.classes().collect();
+        classes.sort_by(|a, b| b.order.cmp(&a.order).then(a.representative.cmp(&b.representative)));
+        classes.truncate(top);
+        classes
+    }
+}
+
+impl Default for EquivalenceClasses {
+    fn default() -> Self {
+        EquivalenceClasses::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cycles;
+    use crate::rubiks::RubiksCube3x3;
+    use crate::search::CanonicalSequences;
+
+    fn key(classes: &EquivalenceClasses, algorithm: &str) -> Vec<Move> {
+        classes.key(&algorithm.parse::<Algorithm>().unwrap())
+    }
+
+    fn order_of(moves: &[Move]) -> u64 {
+        let mut rc = RubiksCube3x3::new();
+        moves.iter().for_each(|&m| rc.apply(m));
+        cycles::order(&rc)
+    }
+
+    #[test]
+    fn key_ignores_rotation_inversion_and_symmetry() {
+        let classes = EquivalenceClasses::new();
+        let r_u_f = key(&classes, "R U F");
+
+        assert_eq!(r_u_f, key(&classes, "U F R"));
+        assert_eq!(r_u_f, key(&classes, "F' U' R'"));
+        assert_eq!(r_u_f, key(&classes, "L' U' F'"));
+        assert_eq!(r_u_f, key(&classes, "U' R' F'"));
+        assert_ne!(r_u_f, key(&classes, "R U' F"));
+    }
+
+    #[test]
+    fn unrotated_key_keeps_apart_sequences_started_part_way_through() {
+        let classes = EquivalenceClasses::unrotated();
+        let r_u2_f = key(&classes, "R U2 F'");
+
+        assert_ne!(r_u2_f, key(&classes, "U2 F' R"));
+        assert_eq!(r_u2_f, key(&classes, "F U2 R'"));
+        assert_eq!(r_u2_f, key(&classes, "L' U2 F"));
+        assert_eq!(key(&classes, "U2 F' R"), key(&classes.emptied(), "U2 F' R"));
+    }
+
+    #[test]
+    fn key_is_a_member_of_the_class() {
+        let classes = EquivalenceClasses::new();
+
+        assert_eq!(vec![Move::U, Move::R], key(&classes, "R U"));
+    }
+
+    #[test]
+    fn classes_share_an_order_and_count_every_sequence() {
+        let pool: Vec<Move> = Move::ALL
+            .into_iter()
+            .filter(|m| m.layer().kind() == LayerKind::Face)
+            .collect();
+        let sequences = CanonicalSequences::new(&pool);
+        let mut classes = EquivalenceClasses::new();
+        let mut count = 0;
+
+        sequences.for_each(3, |sequence| {
+            let order = order_of(sequence);
+            classes.add(sequence, order);
+            assert_eq!(order, order_of(&classes.key(sequence)));
+            count += 1;
+        });
+
+        assert_eq!(count, classes.classes().map(|class| class.size).sum::<usize>());
+        assert!(classes.len() < count / 20);
+        for class in classes.classes() {
+            assert_eq!(class.order, order_of(&class.representative));
+        }
+    }
+
+    #[test]
+    fn merge_matches_adding_everything_to_one() {
+        let sequences: Vec<Algorithm> = ["R U", "U' R'", "R U'", "L' U'", "U F", "R U F"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+
+        let mut all = EquivalenceClasses::new();
+        let mut first = EquivalenceClasses::new();
+        let mut second = EquivalenceClasses::new();
+
+        for (i, sequence) in sequences.iter().enumerate() {
+            all.add(sequence, order_of(sequence));
+            if i % 2 == 0 { &mut first } else { &mut second }.add(sequence, order_of(sequence));
+        }
+        second.merge(first);
+
+        assert_eq!(all.classes().collect::<Vec<_>>(), second.classes().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,659 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::moves::*;
+use crate::optimal;
+use crate::rubiks::*;
+
+type Faces = [[[Colour; 3]; 3]; 6];
+
+/// A 3x3x3 cube described by its 54 stickers, six faces of 3x3 colours.
+///
+/// Each face is stored as it is seen when looking straight at it with the
+/// cube held in its usual orientation: U with B at the top, D with F at the
+/// top, and the four side faces with U at the top.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct FaceletCube {
+    faces: Faces,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum FaceletError {
+    /// The sticker string did not have exactly 54 colours.
+    Length(usize),
+    /// The character at `index` is not a colour letter.
+    Colour { index: usize, found: char },
+    /// The stickers at this position do not belong to any cubie.
+    UnknownCubie(Cubie),
+    /// Two positions have stickers belonging to the same cubie.
+    DuplicateCubie(Cubie),
+    /// The centres are not where any way of holding the cube puts them.
+    Centres,
+    /// The corner twists add up to this many thirds of a turn, where every
+    /// move keeps them adding up to whole turns.
+    CornerTwist(u8),
+    /// An odd number of edges are flipped, where every move flips an even
+    /// number.
+    EdgeFlip,
+    /// The pieces are arranged by an odd number of swaps, where every move
+    /// makes an even number.
+    Parity,
+}
+
+impl fmt::Display for FaceletError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FaceletError::Length(length) =>
+                write!(f, "expected 54 stickers, found {}", length),
+            FaceletError::Colour { index, found } =>
+                write!(f, "'{}' at position {} is not a colour", found, index),
+            FaceletError::UnknownCubie(position) =>
+                write!(f, "stickers at {:?} do not form a cubie", position),
+            FaceletError::DuplicateCubie(cubie) =>
+                write!(f, "cubie {:?} appears more than once", cubie),
+            FaceletError::Centres =>
+                write!(f, "no way of holding the cube puts the centres where they are"),
+            FaceletError::CornerTwist(twist) =>
+                write!(f, "corners are twisted {}/3 of a turn out", twist),
+            FaceletError::EdgeFlip =>
+                write!(f, "an odd number of edges are flipped"),
+            FaceletError::Parity =>
+                write!(f, "the pieces are an odd number of swaps from solved"),
+        }
+    }
+}
+
+impl std::error::Error for FaceletError {}
+
+/// The cell a sticker belongs to, and the axis it faces along. Cells are
+/// indexed front to back, up to down, then left to right.
+fn location(face: Face, row: usize, col: usize) -> ([usize; 3], usize) {
+    match face {
+        Face::U => ([2 - row, 0, col], 1),
+        Face::D => ([row, 2, col], 1),
+        Face::F => ([0, row, col], 0),
+        Face::B => ([2, row, 2 - col], 0),
+        Face::R => ([col, row, 2], 2),
+        Face::L => ([2 - col, row, 0], 2),
+    }
+}
+
+/// The sticker of `cell` facing along `axis`, if it has one.
+fn facelet(cell: [usize; 3], axis: usize) -> Option<(Face, usize, usize)> {
+    let [x, y, z] = cell;
+
+    match (axis, cell[axis]) {
+        (0, 0) => Some((Face::F, y, z)),
+        (0, 2) => Some((Face::B, y, 2 - z)),
+        (1, 0) => Some((Face::U, 2 - x, z)),
+        (1, 2) => Some((Face::D, x, z)),
+        (2, 0) => Some((Face::L, y, 2 - x)),
+        (2, 2) => Some((Face::R, y, x)),
+        _ => None,
+    }
+}
+
+/// The axis a layer turns about, the indices along that axis it covers, and
+/// how many clockwise quarter turns make up a quarter turn of that layer.
+fn geometry(layer: Layer) -> (usize, &'static [usize], usize) {
+    match layer {
+        Layer::U => (1, &[0], 1),
+        Layer::E => (1, &[1], 3),
+        Layer::D => (1, &[2], 3),
+        Layer::Uw => (1, &[0, 1], 1),
+        Layer::Dw => (1, &[1, 2], 3),
+        Layer::Y => (1, &[0, 1, 2], 1),
+        Layer::R => (2, &[2], 1),
+        Layer::M => (2, &[1], 3),
+        Layer::L => (2, &[0], 3),
+        Layer::Rw => (2, &[1, 2], 1),
+        Layer::Lw => (2, &[0, 1], 3),
+        Layer::X => (2, &[0, 1, 2], 1),
+        Layer::F => (0, &[0], 1),
+        Layer::S => (0, &[1], 1),
+        Layer::B => (0, &[2], 3),
+        Layer::Fw => (0, &[0, 1], 1),
+        Layer::Bw => (0, &[1, 2], 3),
+        Layer::Z => (0, &[0, 1, 2], 1),
+    }
+}
+
+/// Where a cell ends up after a quarter turn about `axis`, clockwise as seen
+/// from the F, U and R faces respectively.
+fn quarter_turn(cell: [usize; 3], axis: usize) -> [usize; 3] {
+    let [x, y, z] = cell;
+
+    match axis {
+        0 => [x, z, 2 - y],
+        1 => [2 - z, y, x],
+        _ => [2 - y, x, z],
+    }
+}
+
+impl Default for FaceletCube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FaceletCube {
+    pub fn new() -> Self {
+        let mut faces = [[[Colour::Red; 3]; 3]; 6];
+
+        for face in Face::ALL {
+            faces[face as usize] = [[face.colour(); 3]; 3];
+        }
+
+        FaceletCube { faces }
+    }
+
+    pub fn face(&self, face: Face) -> &[[Colour; 3]; 3] {
+        &self.faces[face as usize]
+    }
+
+    pub fn solved(&self) -> bool {
+        *self == FaceletCube::new()
+    }
+
+    /// Turns the layer at index `layer` along `axis` by `quarters` clockwise
+    /// quarter turns, moving each of its stickers to its new place.
+    fn turn(&mut self, axis: usize, layer: usize, quarters: usize) {
+        let mut faces = self.faces;
+
+        for face in Face::ALL {
+            for row in 0..3 {
+                for col in 0..3 {
+                    let (mut cell, mut facing) = location(face, row, col);
+
+                    if cell[axis] != layer {
+                        continue;
+                    }
+
+                    for _ in 0..quarters {
+                        cell = quarter_turn(cell, axis);
+                        if facing != axis {
+                            facing = 3 - axis - facing;
+                        }
+                    }
+
+                    let (to_face, to_row, to_col) = facelet(cell, facing).unwrap();
+                    faces[to_face as usize][to_row][to_col] = self.faces[face as usize][row][col];
+                }
+            }
+        }
+
+        self.faces = faces;
+    }
+
+    pub fn apply(&mut self, m: Move) {
+        let (axis, layers, quarters) = geometry(m.layer());
+
+        for &layer in layers {
+            self.turn(axis, layer, quarters * m.turn().quarters() % 4);
+        }
+    }
+
+    pub fn single_u(&mut self) {
+        self.apply(Move::U);
+    }
+
+    pub fn single_r(&mut self) {
+        self.apply(Move::R);
+    }
+
+    pub fn single_f(&mut self) {
+        self.apply(Move::F);
+    }
+
+    pub fn single_d(&mut self) {
+        self.apply(Move::D);
+    }
+
+    pub fn single_l(&mut self) {
+        self.apply(Move::L);
+    }
+
+    pub fn single_b(&mut self) {
+        self.apply(Move::B);
+    }
+
+    pub fn single_m(&mut self) {
+        self.apply(Move::M);
+    }
+
+    pub fn single_e(&mut self) {
+        self.apply(Move::E);
+    }
+
+    pub fn single_s(&mut self) {
+        self.apply(Move::S);
+    }
+
+    pub fn single_u_prime(&mut self) {
+        self.apply(Move::UPrime);
+    }
+
+    pub fn single_r_prime(&mut self) {
+        self.apply(Move::RPrime);
+    }
+
+    pub fn single_f_prime(&mut self) {
+        self.apply(Move::FPrime);
+    }
+
+    pub fn single_d_prime(&mut self) {
+        self.apply(Move::DPrime);
+    }
+
+    pub fn single_l_prime(&mut self) {
+        self.apply(Move::LPrime);
+    }
+
+    pub fn single_b_prime(&mut self) {
+        self.apply(Move::BPrime);
+    }
+
+    pub fn single_m_prime(&mut self) {
+        self.apply(Move::MPrime);
+    }
+
+    pub fn single_e_prime(&mut self) {
+        self.apply(Move::EPrime);
+    }
+
+    pub fn single_s_prime(&mut self) {
+        self.apply(Move::SPrime);
+    }
+}
+
+type Facelet = (Face, usize, usize);
+
+/// The stickers of each corner position, starting from its U or D sticker
+/// and going clockwise.
+const CORNER_FACELETS: [[Facelet; 3]; 8] = [
+    [(Face::U, 2, 2), (Face::R, 0, 0), (Face::F, 0, 2)],
+    [(Face::U, 2, 0), (Face::F, 0, 0), (Face::L, 0, 2)],
+    [(Face::U, 0, 0), (Face::L, 0, 0), (Face::B, 0, 2)],
+    [(Face::U, 0, 2), (Face::B, 0, 0), (Face::R, 0, 2)],
+    [(Face::D, 0, 2), (Face::F, 2, 2), (Face::R, 2, 0)],
+    [(Face::D, 0, 0), (Face::L, 2, 2), (Face::F, 2, 0)],
+    [(Face::D, 2, 0), (Face::B, 2, 2), (Face::L, 2, 0)],
+    [(Face::D, 2, 2), (Face::R, 2, 2), (Face::B, 2, 0)],
+];
+
+/// The stickers of each edge position, starting from its U or D sticker, or
+/// its F or B sticker for the middle layer.
+const EDGE_FACELETS: [[Facelet; 2]; 12] = [
+    [(Face::U, 1, 2), (Face::R, 0, 1)],
+    [(Face::U, 2, 1), (Face::F, 0, 1)],
+    [(Face::U, 1, 0), (Face::L, 0, 1)],
+    [(Face::U, 0, 1), (Face::B, 0, 1)],
+    [(Face::D, 1, 2), (Face::R, 2, 1)],
+    [(Face::D, 0, 1), (Face::F, 2, 1)],
+    [(Face::D, 1, 0), (Face::L, 2, 1)],
+    [(Face::D, 2, 1), (Face::B, 2, 1)],
+    [(Face::F, 1, 2), (Face::R, 1, 0)],
+    [(Face::F, 1, 0), (Face::L, 1, 2)],
+    [(Face::B, 1, 2), (Face::L, 1, 0)],
+    [(Face::B, 1, 0), (Face::R, 1, 2)],
+];
+
+impl FaceletCube {
+    fn sticker(&self, (face, row, col): Facelet) -> Colour {
+        self.faces[face as usize][row][col]
+    }
+
+    fn set_sticker(&mut self, (face, row, col): Facelet, colour: Colour) {
+        self.faces[face as usize][row][col] = colour;
+    }
+}
+
+/// The colours of a cubie in the order of the stickers of its home position.
+fn colours<const N: usize>(facelets: &[Facelet; N]) -> [Colour; N] {
+    facelets.map(|(face, _, _)| face.colour())
+}
+
+impl From<&RubiksCube3x3> for FaceletCube {
+    fn from(rc: &RubiksCube3x3) -> Self {
+        let mut fc = FaceletCube::new();
+
+        for (position, facelets) in CORNER_FACELETS.iter().enumerate() {
+            let cubie = colours(&CORNER_FACELETS[rc.corners[position] as usize]);
+            for (n, colour) in cubie.into_iter().enumerate() {
+                fc.set_sticker(facelets[(n + rc.twists[position] as usize) % 3], colour);
+            }
+        }
+
+        for (position, facelets) in EDGE_FACELETS.iter().enumerate() {
+            let cubie = colours(&EDGE_FACELETS[rc.edges[position] as usize]);
+            for (n, colour) in cubie.into_iter().enumerate() {
+                fc.set_sticker(facelets[(n + rc.flips[position] as usize) % 2], colour);
+            }
+        }
+
+        for face in Face::ALL {
+            fc.set_sticker((face, 1, 1), rc.centres[face as usize].colour());
+        }
+
+        fc
+    }
+}
+
+impl TryFrom<&FaceletCube> for RubiksCube3x3 {
+    type Error = FaceletError;
+
+    fn try_from(fc: &FaceletCube) -> Result<Self, Self::Error> {
+        let mut rc = RubiksCube3x3::new();
+        let mut seen = vec![];
+        let mut check = |cubie: Cubie| if seen.contains(&cubie) {
+            Err(FaceletError::DuplicateCubie(cubie))
+        } else {
+            seen.push(cubie);
+            Ok(())
+        };
+
+        for position in Corner::ALL {
+            let stickers = CORNER_FACELETS[position as usize].map(|facelet| fc.sticker(facelet));
+            let unknown = FaceletError::UnknownCubie(Cubie::Corner(position));
+
+            let twist = (0..3)
+                .find(|&n| matches!(stickers[n], Colour::Yellow | Colour::White))
+                .ok_or(unknown.clone())?;
+            let turned = [0, 1, 2].map(|n| stickers[(n + twist) % 3]);
+            let corner = Corner::ALL.into_iter()
+                .find(|&corner| colours(&CORNER_FACELETS[corner as usize]) == turned)
+                .ok_or(unknown)?;
+
+            check(Cubie::Corner(corner))?;
+            rc.corners[position as usize] = corner;
+            rc.twists[position as usize] = twist as u8;
+        }
+
+        for position in Edge::ALL {
+            let stickers = EDGE_FACELETS[position as usize].map(|facelet| fc.sticker(facelet));
+
+            let (edge, flip) = Edge::ALL.into_iter()
+                .flat_map(|edge| [(edge, 0), (edge, 1)])
+                .find(|&(edge, flip)| {
+                    let colours = colours(&EDGE_FACELETS[edge as usize]);
+                    colours == [stickers[flip], stickers[1 - flip]]
+                })
+                .ok_or(FaceletError::UnknownCubie(Cubie::Edge(position)))?;
+
+            check(Cubie::Edge(edge))?;
+            rc.edges[position as usize] = edge;
+            rc.flips[position as usize] = flip as u8;
+        }
+
+        for position in Face::ALL {
+            let colour = fc.sticker((position, 1, 1));
+            let centre = Face::ALL.into_iter()
+                .find(|face| face.colour() == colour)
+                .unwrap();
+
+            check(Cubie::Centre(centre))?;
+            rc.centres[position as usize] = centre;
+        }
+
+        let held = optimal::rotations().into_iter().any(|rotation| {
+            let mut turned = RubiksCube3x3::new();
+            rotation.apply(&mut turned);
+            turned.centres == rc.centres
+        });
+        if !held {
+            return Err(FaceletError::Centres);
+        }
+
+        let twist = rc.twists.iter().sum::<u8>() % 3;
+        if twist != 0 {
+            return Err(FaceletError::CornerTwist(twist));
+        }
+        if rc.flips.iter().sum::<u8>() % 2 != 0 {
+            return Err(FaceletError::EdgeFlip);
+        }
+
+        let swaps = odd(&rc.corners.map(|corner| corner as usize))
+            ^ odd(&rc.edges.map(|edge| edge as usize))
+            ^ odd(&rc.centres.map(|centre| centre as usize));
+        if swaps {
+            return Err(FaceletError::Parity);
+        }
+
+        Ok(rc)
+    }
+}
+
+/// Whether a permutation, stored as the piece in each position, takes an
+/// odd number of swaps to make.
+fn odd(pieces: &[usize]) -> bool {
+    let inversions = (0..pieces.len())
+        .flat_map(|i| (i + 1..pieces.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| pieces[i] > pieces[j])
+        .count();
+
+    inversions % 2 == 1
+}
+
+/// Stickers are written face by face in U, R, F, D, L, B order, each face
+/// row by row, one colour letter (`R`, `O`, `Y`, `W`, `B`, `G`) per sticker.
+impl fmt::Display for FaceletCube {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.faces
+            .iter()
+            .flatten()
+            .flatten()
+            .try_for_each(|colour| write!(f, "{}", colour.letter()))
+    }
+}
+
+impl FromStr for FaceletCube {
+    type Err = FaceletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let letters: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+
+        if letters.len() != 54 {
+            return Err(FaceletError::Length(letters.len()));
+        }
+
+        let mut faces = [[[Colour::Red; 3]; 3]; 6];
+
+        for (index, (sticker, &letter)) in faces.iter_mut().flatten().flatten().zip(&letters).enumerate() {
+            *sticker = Colour::from_letter(letter)
+                .ok_or(FaceletError::Colour { index, found: letter })?;
+        }
+
+        Ok(FaceletCube { faces })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type MovePair = (fn(&mut RubiksCube3x3), fn(&mut FaceletCube));
+
+    const MOVES: [MovePair; 18] = [
+        (RubiksCube3x3::single_u, FaceletCube::single_u),
+        (RubiksCube3x3::single_r, FaceletCube::single_r),
+        (RubiksCube3x3::single_f, FaceletCube::single_f),
+        (RubiksCube3x3::single_d, FaceletCube::single_d),
+        (RubiksCube3x3::single_l, FaceletCube::single_l),
+        (RubiksCube3x3::single_b, FaceletCube::single_b),
+        (RubiksCube3x3::single_m, FaceletCube::single_m),
+        (RubiksCube3x3::single_e, FaceletCube::single_e),
+        (RubiksCube3x3::single_s, FaceletCube::single_s),
+        (RubiksCube3x3::single_u_prime, FaceletCube::single_u_prime),
+        (RubiksCube3x3::single_r_prime, FaceletCube::single_r_prime),
+        (RubiksCube3x3::single_f_prime, FaceletCube::single_f_prime),
+        (RubiksCube3x3::single_d_prime, FaceletCube::single_d_prime),
+        (RubiksCube3x3::single_l_prime, FaceletCube::single_l_prime),
+        (RubiksCube3x3::single_b_prime, FaceletCube::single_b_prime),
+        (RubiksCube3x3::single_m_prime, FaceletCube::single_m_prime),
+        (RubiksCube3x3::single_e_prime, FaceletCube::single_e_prime),
+        (RubiksCube3x3::single_s_prime, FaceletCube::single_s_prime),
+    ];
+
+    fn scrambled() -> RubiksCube3x3 {
+        let mut rc = RubiksCube3x3::new();
+
+        for i in [0, 10, 2, 6, 13, 7, 4, 17, 8, 1, 12, 5, 3, 15, 11, 9] {
+            MOVES[i].0(&mut rc);
+        }
+
+        rc
+    }
+
+    #[test]
+    fn facelet_cube_solved_when_created() {
+        let fc = FaceletCube::new();
+
+        assert_eq!(true, fc.solved());
+        assert_eq!(FaceletCube::new(), FaceletCube::from(&RubiksCube3x3::new()));
+    }
+
+    #[test]
+    fn single_u_moves_front_stickers_to_the_left() {
+        let mut fc = FaceletCube::new();
+
+        fc.single_u();
+
+        assert_eq!([Colour::Red; 3], fc.face(Face::L)[0]);
+        assert_eq!([Colour::Green; 3], fc.face(Face::F)[0]);
+        assert_eq!([[Colour::Yellow; 3]; 3], *fc.face(Face::U));
+    }
+
+    #[test]
+    fn every_move_agrees_with_cubie_model() {
+        for (cubie_move, facelet_move) in MOVES {
+            let mut rc = scrambled();
+            let mut fc = FaceletCube::from(&rc);
+
+            cubie_move(&mut rc);
+            facelet_move(&mut fc);
+
+            assert_eq!(FaceletCube::from(&rc), fc);
+        }
+    }
+
+    #[test]
+    fn moves_of_every_layer_agree_with_cubie_model() {
+        for m in Move::ALL {
+            let mut rc = scrambled();
+            let mut fc = FaceletCube::from(&rc);
+
+            rc.apply(m);
+            fc.apply(m);
+
+            assert_eq!(FaceletCube::from(&rc), fc, "{}", m);
+        }
+    }
+
+    #[test]
+    fn every_move_solves_if_repeated_four_times() {
+        for (_, facelet_move) in MOVES {
+            let mut fc = FaceletCube::new();
+
+            facelet_move(&mut fc);
+            assert_eq!(false, fc.solved());
+
+            for _ in 0..3 {
+                facelet_move(&mut fc);
+            }
+            assert_eq!(true, fc.solved());
+        }
+    }
+
+    #[test]
+    fn cubie_model_round_trips_through_stickers() {
+        let rc = scrambled();
+
+        let fc = FaceletCube::from(&rc);
+
+        assert_eq!(Ok(rc), RubiksCube3x3::try_from(&fc));
+    }
+
+    #[test]
+    fn stickers_round_trip_through_string() {
+        let fc = FaceletCube::from(&scrambled());
+
+        assert_eq!(Ok(fc), fc.to_string().parse());
+    }
+
+    #[test]
+    fn parse_rejects_wrong_length() {
+        assert_eq!(Err(FaceletError::Length(53)), "Y".repeat(53).parse::<FaceletCube>());
+    }
+
+    #[test]
+    fn parse_rejects_unknown_colour() {
+        let mut stickers = FaceletCube::new().to_string();
+        stickers.replace_range(20..21, "X");
+
+        assert_eq!(Err(FaceletError::Colour { index: 20, found: 'X' }),
+                   stickers.parse::<FaceletCube>());
+    }
+
+    #[test]
+    fn conversion_rejects_impossible_cubies() {
+        // Painting the UFR corner's U sticker orange gives it both red and
+        // orange, which no cubie has.
+        let mut stickers = FaceletCube::new().to_string();
+        stickers.replace_range(8..9, "O");
+        let fc: FaceletCube = stickers.parse().unwrap();
+
+        assert_eq!(Err(FaceletError::UnknownCubie(Cubie::Corner(Corner::URF))),
+                   RubiksCube3x3::try_from(&fc));
+    }
+
+    /// A solved cube with the stickers of `cubies` moved along by `shift`:
+    /// turned in place if they are one cubie's, swapped if two.
+    fn moved_stickers(cubies: &[&[Facelet]], shift: usize) -> FaceletCube {
+        let mut fc = FaceletCube::new();
+        let stickers: Vec<Facelet> = cubies.iter().flat_map(|cubie| cubie.iter().copied()).collect();
+        let colours: Vec<Colour> = stickers.iter().map(|&facelet| fc.sticker(facelet)).collect();
+
+        for (n, &facelet) in stickers.iter().enumerate() {
+            fc.set_sticker(facelet, colours[(n + shift) % colours.len()]);
+        }
+
+        fc
+    }
+
+    #[test]
+    fn conversion_rejects_a_twisted_corner() {
+        let fc = moved_stickers(&[&CORNER_FACELETS[Corner::URF as usize]], 1);
+
+        assert_eq!(Err(FaceletError::CornerTwist(2)), RubiksCube3x3::try_from(&fc));
+    }
+
+    #[test]
+    fn conversion_rejects_a_flipped_edge() {
+        let fc = moved_stickers(&[&EDGE_FACELETS[Edge::UR as usize]], 1);
+
+        assert_eq!(Err(FaceletError::EdgeFlip), RubiksCube3x3::try_from(&fc));
+    }
+
+    #[test]
+    fn conversion_rejects_two_swapped_edges() {
+        let fc = moved_stickers(&[&EDGE_FACELETS[Edge::UR as usize], &EDGE_FACELETS[Edge::UF as usize]], 2);
+
+        assert_eq!(Err(FaceletError::Parity), RubiksCube3x3::try_from(&fc));
+    }
+
+    #[test]
+    fn conversion_rejects_centres_no_rotation_puts_there() {
+        let fc = moved_stickers(&[&[(Face::U, 1, 1)], &[(Face::F, 1, 1)], &[(Face::D, 1, 1)]], 1);
+
+        assert_eq!(Err(FaceletError::Centres), RubiksCube3x3::try_from(&fc));
+    }
+
+    #[test]
+    fn conversion_accepts_every_move_and_slice() {
+        let mut rc = scrambled();
+        rc.apply(Move::M);
+        rc.apply(Move::Y);
+
+        assert_eq!(Ok(rc), RubiksCube3x3::try_from(&FaceletCube::from(&rc)));
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt;
+
+use crate::rubiks::*;
+
+/// What a search is looking for: which of the positions it reaches, with
+/// the order of the sequence that reached them, to keep.
+pub trait Goal: Sync {
+    fn matches(&self, rc: &RubiksCube3x3, order: u64) -> bool;
+}
+
+impl<F: Fn(&RubiksCube3x3, u64) -> bool + Sync> Goal for F {
+    fn matches(&self, rc: &RubiksCube3x3, order: u64) -> bool {
+        self(rc, order)
+    }
+}
+
+fn corner_home(rc: &RubiksCube3x3, corner: Corner) -> bool {
+    rc.corners[corner as usize] == corner && rc.twists[corner as usize] == 0
+}
+
+fn edge_home(rc: &RubiksCube3x3, edge: Edge) -> bool {
+    rc.edges[edge as usize] == edge && rc.flips[edge as usize] == 0
+}
+
+fn centres_home(rc: &RubiksCube3x3) -> bool {
+    rc.centres == Face::ALL
+}
+
+/// Keeps everything.
+pub struct Everything;
+
+impl Goal for Everything {
+    fn matches(&self, _: &RubiksCube3x3, _: u64) -> bool {
+        true
+    }
+}
+
+pub struct OrderAtLeast(pub u64);
+
+impl Goal for OrderAtLeast {
+    fn matches(&self, _: &RubiksCube3x3, order: u64) -> bool {
+        order >= self.0
+    }
+}
+
+pub struct OrderEquals(pub u64);
+
+impl Goal for OrderEquals {
+    fn matches(&self, _: &RubiksCube3x3, order: u64) -> bool {
+        order == self.0
+    }
+}
+
+/// Every corner back where it started, untwisted.
+pub struct PreservesCorners;
+
+impl Goal for PreservesCorners {
+    fn matches(&self, rc: &RubiksCube3x3, _: u64) -> bool {
+        Corner::ALL.into_iter().all(|corner| corner_home(rc, corner))
+    }
+}
+
+/// Nothing moved, but some edges flipped in place.
+pub struct OnlyFlipsEdges;
+
+impl Goal for OnlyFlipsEdges {
+    fn matches(&self, rc: &RubiksCube3x3, _: u64) -> bool {
+        PreservesCorners.matches(rc, 0)
+            && centres_home(rc)
+            && rc.edges == Edge::ALL
+            && rc.flips.iter().any(|&flip| flip != 0)
+    }
+}
+
+/// Only the U layer changed: the D layer and the middle edges, the first
+/// two layers of a layer by layer solve, are all still solved, as an
+/// algorithm for fixing the last layer needs.
+pub struct LastLayerOnly;
+
+impl Goal for LastLayerOnly {
+    fn matches(&self, rc: &RubiksCube3x3, _: u64) -> bool {
+        centres_home(rc)
+            && [Corner::DFR, Corner::DLF, Corner::DBL, Corner::DRB]
+                .into_iter()
+                .all(|corner| corner_home(rc, corner))
+            && [Edge::DR, Edge::DF, Edge::DL, Edge::DB, Edge::FR, Edge::FL, Edge::BL, Edge::BR]
+                .into_iter()
+                .all(|edge| edge_home(rc, edge))
+    }
+}
+
+/// Every one of several goals at once.
+pub struct All(pub Vec<Box<dyn Goal>>);
+
+impl Goal for All {
+    fn matches(&self, rc: &RubiksCube3x3, order: u64) -> bool {
+        self.0.iter().all(|goal| goal.matches(rc, order))
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseGoalError {
+    pub text: String,
+}
+
+impl fmt::Display for ParseGoalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a goal", self.text)
+    }
+}
+
+impl std::error::Error for ParseGoalError {}
+
+/// Reads a goal written as conditions separated by commas, all of which
+/// have to hold: `order>=K`, `order=K`, `preserves-corners`,
+/// `only-flips-edges` and `last-layer-only`.
+pub fn parse_goal(s: &str) -> Result<Box<dyn Goal>, ParseGoalError> {
+    let condition = |text: &str| -> Result<Box<dyn Goal>, ParseGoalError> {
+        let error = || ParseGoalError { text: text.to_string() };
+        let order = |value: &str| value.trim().parse::<u64>().map_err(|_| error());
+
+        if let Some(value) = text.strip_prefix("order>=") {
+            return Ok(Box::new(OrderAtLeast(order(value)?)));
+        }
+        if let Some(value) = text.strip_prefix("order=") {
+            return Ok(Box::new(OrderEquals(order(value)?)));
+        }
+
+        match text {
+            "preserves-corners" => Ok(Box::new(PreservesCorners)),
+            "only-flips-edges" => Ok(Box::new(OnlyFlipsEdges)),
+            "last-layer-only" => Ok(Box::new(LastLayerOnly)),
+            _ => Err(error()),
+        }
+    };
+
+    let goals = s
+        .split(',')
+        .map(|text| condition(text.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Box::new(All(goals)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::Algorithm;
+    use crate::cycles;
+
+    fn matches(goal: &dyn Goal, algorithm: &str) -> bool {
+        let mut rc = RubiksCube3x3::new();
+        algorithm.parse::<Algorithm>().unwrap().apply(&mut rc);
+
+        goal.matches(&rc, cycles::order(&rc))
+    }
+
+    const SUPERFLIP: &str = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+    const T_PERM: &str = "R U R' U' R' F R2 U' R' U' R U R' F'";
+    const U_PERM: &str = "R U' R U R U R U' R' U' R2";
+
+    #[test]
+    fn order_goals_compare_order() {
+        assert_eq!(true, matches(&OrderAtLeast(105), "R U"));
+        assert_eq!(false, matches(&OrderAtLeast(106), "R U"));
+        assert_eq!(true, matches(&OrderEquals(63), "R U'"));
+        assert_eq!(false, matches(&OrderEquals(63), "R U"));
+    }
+
+    #[test]
+    fn preserves_corners_finds_edge_only_algorithms() {
+        assert_eq!(true, matches(&PreservesCorners, "M2 U M2 U2 M2 U M2"));
+        assert_eq!(true, matches(&PreservesCorners, SUPERFLIP));
+        assert_eq!(false, matches(&PreservesCorners, "R U R' U'"));
+    }
+
+    #[test]
+    fn only_flips_edges_needs_edges_in_place_and_some_flipped() {
+        assert_eq!(true, matches(&OnlyFlipsEdges, SUPERFLIP));
+        assert_eq!(false, matches(&OnlyFlipsEdges, "M2 U M2 U2 M2 U M2"));
+        assert_eq!(false, matches(&OnlyFlipsEdges, ""));
+    }
+
+    #[test]
+    fn last_layer_only_keeps_first_two_layers() {
+        assert_eq!(true, matches(&LastLayerOnly, T_PERM));
+        assert_eq!(true, matches(&LastLayerOnly, "U"));
+        assert_eq!(false, matches(&LastLayerOnly, "R U R' U'"));
+        assert_eq!(false, matches(&LastLayerOnly, "M2 U M2 U2 M2 U M2 y"));
+    }
+
+    #[test]
+    fn closures_are_goals() {
+        let short = |_: &RubiksCube3x3, order: u64| order < 10;
+
+        assert_eq!(true, matches(&short, "R U R' U'"));
+        assert_eq!(false, matches(&short, "R U"));
+    }
+
+    #[test]
+    fn parse_goal_combines_conditions() {
+        let goal = parse_goal("order>=3, preserves-corners").unwrap();
+
+        assert_eq!(true, matches(goal.as_ref(), U_PERM));
+        assert_eq!(false, matches(goal.as_ref(), "M2 U M2 U2 M2 U M2"));
+        assert_eq!(false, matches(goal.as_ref(), "R U"));
+        assert_eq!(Err(ParseGoalError { text: String::from("order>=lots") }),
+                   parse_goal("order>=lots").map(|_| ()));
+        assert_eq!(Err(ParseGoalError { text: String::from("solved") }),
+                   parse_goal("solved").map(|_| ()));
+    }
+}
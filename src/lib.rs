@@ -0,0 +1,28 @@
+#![feature(test)]
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
+
+extern crate test;
+
+pub mod transforms;
+pub mod rubiks;
+pub mod facelet;
+pub mod moves;
+pub mod algorithm;
+pub mod cycles;
+pub mod search;
+pub mod symmetry;
+pub mod equivalence;
+pub mod checkpoint;
+pub mod report;
+pub mod sink;
+pub mod statistics;
+pub mod orders;
+pub mod goal;
+pub mod pattern;
+pub mod optimal;
+pub mod two_phase;
+pub mod thistlethwaite;
+pub mod beginner;
+pub mod rubiks2x2;
+pub mod distances;
+pub mod nxn;
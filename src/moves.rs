@@ -0,0 +1,404 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::rubiks::RubiksCube3x3;
+
+/// The axis a layer turns about: `X` through R and L, `Y` through U and D,
+/// `Z` through F and B.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+pub enum Axis {
+    X, Y, Z,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+pub enum LayerKind {
+    Face, Slice, Wide, Rotation,
+}
+
+/// Something on the cube that can be turned: one of the six faces, one of
+/// the three middle slices, a face together with the slice next to it, or
+/// the whole cube.
+///
+/// `M` turns like L, `E` like D and `S` like F. Wide layers turn like their
+/// face, and `X`, `Y` and `Z` turn the whole cube like R, U and F.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+pub enum Layer {
+    U, R, F, D, L, B, M, E, S,
+    Uw, Rw, Fw, Dw, Lw, Bw,
+    X, Y, Z,
+}
+
+impl Layer {
+    pub const ALL: [Layer; 18] = [
+        Layer::U, Layer::R, Layer::F, Layer::D, Layer::L, Layer::B, Layer::M, Layer::E, Layer::S,
+        Layer::Uw, Layer::Rw, Layer::Fw, Layer::Dw, Layer::Lw, Layer::Bw,
+        Layer::X, Layer::Y, Layer::Z,
+    ];
+
+    pub fn axis(self) -> Axis {
+        match self {
+            Layer::R | Layer::L | Layer::M | Layer::Rw | Layer::Lw | Layer::X => Axis::X,
+            Layer::U | Layer::D | Layer::E | Layer::Uw | Layer::Dw | Layer::Y => Axis::Y,
+            Layer::F | Layer::B | Layer::S | Layer::Fw | Layer::Bw | Layer::Z => Axis::Z,
+        }
+    }
+
+    pub fn kind(self) -> LayerKind {
+        match self {
+            Layer::U | Layer::R | Layer::F | Layer::D | Layer::L | Layer::B => LayerKind::Face,
+            Layer::M | Layer::E | Layer::S => LayerKind::Slice,
+            Layer::X | Layer::Y | Layer::Z => LayerKind::Rotation,
+            _ => LayerKind::Wide,
+        }
+    }
+}
+
+/// How far a layer is turned, looking at it from its own side.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+pub enum Turn {
+    Quarter, Half, Prime,
+}
+
+impl Turn {
+    pub const ALL: [Turn; 3] = [Turn::Quarter, Turn::Half, Turn::Prime];
+
+    /// The number of clockwise quarter turns this turn is made of.
+    pub fn quarters(self) -> usize {
+        match self {
+            Turn::Quarter => 1,
+            Turn::Half => 2,
+            Turn::Prime => 3,
+        }
+    }
+
+    pub fn inverse(self) -> Turn {
+        match self {
+            Turn::Quarter => Turn::Prime,
+            Turn::Half => Turn::Half,
+            Turn::Prime => Turn::Quarter,
+        }
+    }
+}
+
+/// A single turn of one layer of the cube.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+pub enum Move {
+    U, U2, UPrime,
+    R, R2, RPrime,
+    F, F2, FPrime,
+    D, D2, DPrime,
+    L, L2, LPrime,
+    B, B2, BPrime,
+    M, M2, MPrime,
+    E, E2, EPrime,
+    S, S2, SPrime,
+    Uw, Uw2, UwPrime,
+    Rw, Rw2, RwPrime,
+    Fw, Fw2, FwPrime,
+    Dw, Dw2, DwPrime,
+    Lw, Lw2, LwPrime,
+    Bw, Bw2, BwPrime,
+    X, X2, XPrime,
+    Y, Y2, YPrime,
+    Z, Z2, ZPrime,
+}
+
+impl Move {
+    pub const ALL: [Move; 54] = [
+        Move::U, Move::U2, Move::UPrime,
+        Move::R, Move::R2, Move::RPrime,
+        Move::F, Move::F2, Move::FPrime,
+        Move::D, Move::D2, Move::DPrime,
+        Move::L, Move::L2, Move::LPrime,
+        Move::B, Move::B2, Move::BPrime,
+        Move::M, Move::M2, Move::MPrime,
+        Move::E, Move::E2, Move::EPrime,
+        Move::S, Move::S2, Move::SPrime,
+        Move::Uw, Move::Uw2, Move::UwPrime,
+        Move::Rw, Move::Rw2, Move::RwPrime,
+        Move::Fw, Move::Fw2, Move::FwPrime,
+        Move::Dw, Move::Dw2, Move::DwPrime,
+        Move::Lw, Move::Lw2, Move::LwPrime,
+        Move::Bw, Move::Bw2, Move::BwPrime,
+        Move::X, Move::X2, Move::XPrime,
+        Move::Y, Move::Y2, Move::YPrime,
+        Move::Z, Move::Z2, Move::ZPrime,
+    ];
+
+    pub fn new(layer: Layer, turn: Turn) -> Move {
+        Move::ALL[layer as usize * 3 + turn as usize]
+    }
+
+    pub fn layer(self) -> Layer {
+        Layer::ALL[self as usize / 3]
+    }
+
+    pub fn turn(self) -> Turn {
+        Turn::ALL[self as usize % 3]
+    }
+
+    pub fn axis(self) -> Axis {
+        self.layer().axis()
+    }
+
+    pub fn inverse(self) -> Move {
+        Move::new(self.layer(), self.turn().inverse())
+    }
+
+    pub fn apply(self, rc: &mut RubiksCube3x3) {
+        rc.apply(self);
+    }
+}
+
+/// Rotations are written in lower case, as `x`, `y` and `z`.
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind() {
+            LayerKind::Rotation => write!(f, "{}", format!("{:?}", self).to_lowercase()),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let suffix = match self.turn() {
+            Turn::Quarter => "",
+            Turn::Half => "2",
+            Turn::Prime => "'",
+        };
+
+        write!(f, "{}{}", self.layer(), suffix)
+    }
+}
+
+/// A way of counting how many moves a sequence is.
+///
+/// `Htm`, the half turn metric, counts any turn of a face as one move and a
+/// slice turn as two, since it turns two faces as far. `Qtm`, the quarter
+/// turn metric, counts quarter turns, so half turns of a face count two.
+/// `Stm`, the slice turn metric, counts any turn of a face or slice as one.
+/// Wide turns count like face turns, and rotations as one move in every
+/// metric.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Metric {
+    Htm, Qtm, Stm,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 3] = [Metric::Htm, Metric::Qtm, Metric::Stm];
+
+    pub fn cost(self, m: Move) -> usize {
+        let kind = m.layer().kind();
+
+        let layers = match (self, kind) {
+            (Metric::Htm | Metric::Qtm, LayerKind::Slice) => 2,
+            _ => 1,
+        };
+        let turns = match (self, kind, m.turn()) {
+            (Metric::Qtm, LayerKind::Face | LayerKind::Slice | LayerKind::Wide, Turn::Half) => 2,
+            _ => 1,
+        };
+
+        layers * turns
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseMetricError {
+    pub name: String,
+}
+
+impl fmt::Display for ParseMetricError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a metric, expected htm, qtm or stm", self.name)
+    }
+}
+
+impl std::error::Error for ParseMetricError {}
+
+impl FromStr for Metric {
+    type Err = ParseMetricError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Metric::ALL
+            .into_iter()
+            .find(|metric| metric.to_string() == s.to_lowercase())
+            .ok_or_else(|| ParseMetricError { name: s.to_string() })
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ParseMoveError {
+    pub token: String,
+}
+
+impl fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a move", self.token)
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+/// Parses a move in standard notation: a layer, then `2` for a half turn or
+/// `'` for a prime turn. Wide turns may be written `Rw` or `r`, and `R2'` is
+/// accepted as a half turn.
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseMoveError { token: s.to_string() };
+
+        let split = s.find(['2', '\'', '’']).unwrap_or(s.len());
+        let (name, suffix) = s.split_at(split);
+
+        let name = match name {
+            "u" | "r" | "f" | "d" | "l" | "b" => format!("{}w", name.to_uppercase()),
+            _ => name.to_string(),
+        };
+
+        let layer = Layer::ALL
+            .into_iter()
+            .find(|layer| layer.to_string() == name)
+            .ok_or_else(error)?;
+
+        let turn = match suffix {
+            "" => Turn::Quarter,
+            "2" | "2'" | "2’" => Turn::Half,
+            "'" | "’" => Turn::Prime,
+            _ => return Err(error()),
+        };
+
+        Ok(Move::new(layer, turn))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rubiks::RubiksCube;
+
+    #[test]
+    fn new_finds_move_with_layer_and_turn() {
+        for m in Move::ALL {
+            assert_eq!(m, Move::new(m.layer(), m.turn()));
+        }
+
+        assert_eq!(Move::MPrime, Move::new(Layer::M, Turn::Prime));
+    }
+
+    #[test]
+    fn inverse_undoes_move() {
+        for m in Move::ALL {
+            let mut rc = RubiksCube3x3::new();
+
+            m.apply(&mut rc);
+            assert_eq!(false, rc.solved());
+
+            m.inverse().apply(&mut rc);
+            assert_eq!(true, rc.solved());
+        }
+    }
+
+    #[test]
+    fn half_turn_is_two_quarter_turns() {
+        for layer in Layer::ALL {
+            let mut quarters = RubiksCube3x3::new();
+            quarters.apply(Move::new(layer, Turn::Quarter));
+            quarters.apply(Move::new(layer, Turn::Quarter));
+
+            let mut half = RubiksCube3x3::new();
+            half.apply(Move::new(layer, Turn::Half));
+
+            assert_eq!(quarters, half);
+        }
+    }
+
+    #[test]
+    fn axis_groups_parallel_layers() {
+        assert_eq!(Axis::X, Move::LPrime.axis());
+        assert_eq!(Axis::Y, Move::E2.axis());
+        assert_eq!(Axis::Z, Move::S.axis());
+    }
+
+    #[test]
+    fn display_uses_standard_notation() {
+        assert_eq!("R", Move::R.to_string());
+        assert_eq!("U2", Move::U2.to_string());
+        assert_eq!("M'", Move::MPrime.to_string());
+        assert_eq!("Bw2", Move::Bw2.to_string());
+        assert_eq!("z'", Move::ZPrime.to_string());
+    }
+
+    #[test]
+    fn from_str_parses_display() {
+        for m in Move::ALL {
+            assert_eq!(Ok(m), m.to_string().parse());
+        }
+    }
+
+    #[test]
+    fn from_str_parses_wide_moves_in_either_case() {
+        assert_eq!(Ok(Move::Rw), "Rw".parse());
+        assert_eq!(Ok(Move::Rw), "r".parse());
+        assert_eq!(Ok(Move::DwPrime), "d'".parse());
+        assert_eq!(Ok(Move::XPrime), "x'".parse());
+        assert_eq!(Ok(Move::U2), "U2'".parse());
+    }
+
+    #[test]
+    fn wide_move_turns_face_and_slice() {
+        let mut wide = RubiksCube3x3::new();
+        wide.apply(Move::Rw);
+
+        let mut layers = RubiksCube3x3::new();
+        layers.apply(Move::R);
+        layers.apply(Move::MPrime);
+
+        assert_eq!(layers, wide);
+    }
+
+    #[test]
+    fn rotation_turns_every_layer() {
+        let mut rotated = RubiksCube3x3::new();
+        rotated.apply(Move::Y);
+
+        let mut layers = RubiksCube3x3::new();
+        layers.apply(Move::U);
+        layers.apply(Move::EPrime);
+        layers.apply(Move::DPrime);
+
+        assert_eq!(layers, rotated);
+    }
+
+    #[test]
+    fn metrics_count_half_turns_and_slices_differently() {
+        assert_eq!([1, 1, 1], Metric::ALL.map(|metric| metric.cost(Move::R)));
+        assert_eq!([1, 2, 1], Metric::ALL.map(|metric| metric.cost(Move::R2)));
+        assert_eq!([2, 2, 1], Metric::ALL.map(|metric| metric.cost(Move::MPrime)));
+        assert_eq!([2, 4, 1], Metric::ALL.map(|metric| metric.cost(Move::E2)));
+        assert_eq!([1, 2, 1], Metric::ALL.map(|metric| metric.cost(Move::Rw2)));
+        assert_eq!([1, 1, 1], Metric::ALL.map(|metric| metric.cost(Move::Y2)));
+    }
+
+    #[test]
+    fn metric_parses_its_name() {
+        assert_eq!(Ok(Metric::Qtm), "qtm".parse());
+        assert_eq!(Ok(Metric::Htm), "HTM".parse());
+        assert!("atm".parse::<Metric>().is_err());
+    }
+
+    #[test]
+    fn from_str_rejects_unknown_move() {
+        assert_eq!(Err(ParseMoveError { token: String::from("Q2") }), "Q2".parse::<Move>());
+        assert_eq!(Err(ParseMoveError { token: String::from("Mw") }), "Mw".parse::<Move>());
+        assert_eq!(Err(ParseMoveError { token: String::from("R3") }), "R3".parse::<Move>());
+    }
+}
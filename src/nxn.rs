@@ -0,0 +1,632 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::cycles::lcm;
+use crate::moves::*;
+use crate::rubiks::*;
+use crate::transforms::*;
+
+/// The sizes of cube `RubiksCubeNxN` models.
+pub const SIZES: RangeInclusive<usize> = 2..=7;
+
+/// A turn of an NxNxN cube: either a move in standard notation, whose
+/// layers depend on the size of the cube, or a block of layers counted in
+/// from a face.
+///
+/// On an NxNxN cube faces turn their outer layer, wide moves the outer two,
+/// `M`, `E` and `S` every layer between two faces, and rotations every
+/// layer. A block turns layers `outer` to `inner`, counting the face as 1,
+/// in the direction the face turns.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum LayerMove {
+    Standard(Move),
+    Block { face: Face, outer: usize, inner: usize, turn: Turn },
+}
+
+impl LayerMove {
+    pub fn turn(self) -> Turn {
+        match self {
+            LayerMove::Standard(m) => m.turn(),
+            LayerMove::Block { turn, .. } => turn,
+        }
+    }
+
+    pub fn inverse(self) -> LayerMove {
+        match self {
+            LayerMove::Standard(m) => LayerMove::Standard(m.inverse()),
+            LayerMove::Block { face, outer, inner, turn } =>
+                LayerMove::Block { face, outer, inner, turn: turn.inverse() },
+        }
+    }
+
+    /// The face the move turns like, and the layers it turns on a cube of
+    /// `size`, counted in from that face.
+    fn block(self, size: usize) -> (Face, RangeInclusive<usize>) {
+        let m = match self {
+            LayerMove::Block { face, outer, inner, .. } => return (face, outer..=inner),
+            LayerMove::Standard(m) => m,
+        };
+
+        match m.layer() {
+            Layer::U => (Face::U, 1..=1),
+            Layer::R => (Face::R, 1..=1),
+            Layer::F => (Face::F, 1..=1),
+            Layer::D => (Face::D, 1..=1),
+            Layer::L => (Face::L, 1..=1),
+            Layer::B => (Face::B, 1..=1),
+            Layer::M => (Face::L, 2..=size - 1),
+            Layer::E => (Face::D, 2..=size - 1),
+            Layer::S => (Face::F, 2..=size - 1),
+            Layer::Uw => (Face::U, 1..=2),
+            Layer::Rw => (Face::R, 1..=2),
+            Layer::Fw => (Face::F, 1..=2),
+            Layer::Dw => (Face::D, 1..=2),
+            Layer::Lw => (Face::L, 1..=2),
+            Layer::Bw => (Face::B, 1..=2),
+            Layer::X => (Face::R, 1..=size),
+            Layer::Y => (Face::U, 1..=size),
+            Layer::Z => (Face::F, 1..=size),
+        }
+    }
+
+    /// Whether the move turns any layers of a cube of `size`, and none
+    /// deeper than it has. Slices turn none on a 2x2x2.
+    pub fn fits(self, size: usize) -> bool {
+        let (_, layers) = self.block(size);
+        !layers.is_empty() && *layers.end() <= size
+    }
+
+    /// Every distinct turn of a single layer of a cube of `size`, faces and
+    /// inner slices alike. The middle layers of odd cubes turn like L, D
+    /// and F, as `M`, `E` and `S` do.
+    pub fn single_layers(size: usize) -> Vec<LayerMove> {
+        let outside = Face::ALL.into_iter().flat_map(|face| (1..=size / 2).map(move |layer| (face, layer)));
+        let middle = [Face::L, Face::D, Face::F].map(|face| (face, size / 2 + 1));
+        let middle = middle.iter().copied().filter(|_| size % 2 == 1);
+
+        outside
+            .chain(middle)
+            .flat_map(|(face, layer)| Turn::ALL.map(|turn| match (layer, size, face) {
+                (1, _, _) => LayerMove::Standard(Move::new(face_layer(face), turn)),
+                (2, 3, Face::L) => LayerMove::Standard(Move::new(Layer::M, turn)),
+                (2, 3, Face::D) => LayerMove::Standard(Move::new(Layer::E, turn)),
+                (2, 3, Face::F) => LayerMove::Standard(Move::new(Layer::S, turn)),
+                _ => LayerMove::Block { face, outer: layer, inner: layer, turn },
+            }))
+            .collect()
+    }
+}
+
+fn face_layer(face: Face) -> Layer {
+    match face {
+        Face::U => Layer::U,
+        Face::R => Layer::R,
+        Face::F => Layer::F,
+        Face::D => Layer::D,
+        Face::L => Layer::L,
+        Face::B => Layer::B,
+    }
+}
+
+impl From<Move> for LayerMove {
+    fn from(m: Move) -> Self {
+        LayerMove::Standard(m)
+    }
+}
+
+/// Blocks are written the way big cube notation has them: `2R` for the
+/// second layer in from R alone, `3Rw` for the outer three, and `2-3Rw` for
+/// the second and third.
+impl fmt::Display for LayerMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (face, outer, inner, turn) = match *self {
+            LayerMove::Standard(m) => return write!(f, "{}", m),
+            LayerMove::Block { face, outer, inner, turn } => (face, outer, inner, turn),
+        };
+
+        let suffix = match turn {
+            Turn::Quarter => "",
+            Turn::Half => "2",
+            Turn::Prime => "'",
+        };
+
+        match (outer, inner) {
+            (outer, inner) if outer == inner => write!(f, "{}{:?}{}", outer, face, suffix),
+            (1, inner) => write!(f, "{}{:?}w{}", inner, face, suffix),
+            (outer, inner) => write!(f, "{}-{}{:?}w{}", outer, inner, face, suffix),
+        }
+    }
+}
+
+/// Parses a move in standard notation, or a block of layers as `Display`
+/// writes it. Wide blocks may also be written in lower case, as `3r`.
+impl FromStr for LayerMove {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(m) = s.parse::<Move>() {
+            return Ok(LayerMove::Standard(m));
+        }
+
+        let error = || ParseMoveError { token: s.to_string() };
+        let number = |digits: &str| digits.parse::<usize>().ok().filter(|&n| n >= 1).ok_or_else(error);
+
+        let start = s.find(|c: char| c.is_ascii_alphabetic()).ok_or_else(error)?;
+        let (layers, name) = s.split_at(start);
+        let mut letters = name.chars();
+        let letter = letters.next().ok_or_else(error)?;
+        let (wide, suffix) = match letters.as_str().strip_prefix('w') {
+            _ if letter.is_ascii_lowercase() => (true, letters.as_str()),
+            Some(suffix) => (true, suffix),
+            None => (false, letters.as_str()),
+        };
+
+        let (outer, inner) = match layers.split_once('-') {
+            Some((outer, inner)) if wide => (number(outer)?, number(inner)?),
+            None if wide => (1, number(layers)?),
+            None => (number(layers)?, number(layers)?),
+            Some(_) => return Err(error()),
+        };
+        if outer > inner {
+            return Err(error());
+        }
+
+        // The face and turn are read as the face turn they would be alone.
+        let m: Move = format!("{}{}", letter.to_ascii_uppercase(), suffix).parse().map_err(|_| error())?;
+        let face = Face::ALL
+            .into_iter()
+            .find(|&face| face_layer(face) == m.layer())
+            .ok_or_else(error)?;
+
+        Ok(LayerMove::Block { face, outer, inner, turn: m.turn() })
+    }
+}
+
+/// One of the small cubes an NxNxN cube is made of: the position it
+/// belongs in, and its stickers, one per axis at most. Cells inside the
+/// cube have none.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+struct Cell {
+    home: [u8; 3],
+    stickers: [Option<Colour>; 3],
+}
+
+impl Cell {
+    /// The cell that belongs at `position` on a solved cube of `size`.
+    fn solved(position: [usize; 3], size: usize) -> Cell {
+        let sticker = |index: usize, near: Face, far: Face| match index {
+            0 => Some(near.colour()),
+            index if index == size - 1 => Some(far.colour()),
+            _ => None,
+        };
+        let [x, y, z] = position;
+
+        Cell {
+            home: position.map(|index| index as u8),
+            stickers: [sticker(x, Face::F, Face::B), sticker(y, Face::U, Face::D), sticker(z, Face::L, Face::R)],
+        }
+    }
+}
+
+/// An NxNxN cube for N from 2 to 7, as the N^3 cells it is made of, so that
+/// each piece is followed wherever it goes, including the centre pieces of
+/// bigger cubes that look the same as others of their colour.
+///
+/// Cells are indexed front to back, up to down, then left to right, as the
+/// stickers of `FaceletCube` are, and turns rotate planes of cells with the
+/// helpers in `transforms`.
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub struct RubiksCubeNxN {
+    size: usize,
+    cells: Vec<Vec<Vec<Cell>>>,
+}
+
+impl RubiksCubeNxN {
+    /// A solved cube of `size`. Panics if `size` is not one of `SIZES`.
+    pub fn new(size: usize) -> Self {
+        assert!(SIZES.contains(&size), "cubes from 2x2x2 to 7x7x7 are modelled, not {}x{}x{}", size, size, size);
+
+        let cells = (0..size)
+            .map(|x| (0..size).map(|y| (0..size).map(|z| Cell::solved([x, y, z], size)).collect()).collect())
+            .collect();
+
+        RubiksCubeNxN { size, cells }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The stickers of `face`, seen as `FaceletCube` has them: U with B at
+    /// the top, D with F at the top, and the side faces with U at the top.
+    pub fn face(&self, face: Face) -> Vec<Vec<Colour>> {
+        let last = self.size - 1;
+        let sticker = |row: usize, col: usize| {
+            let ([x, y, z], axis) = match face {
+                Face::U => ([last - row, 0, col], 1),
+                Face::D => ([row, last, col], 1),
+                Face::F => ([0, row, col], 0),
+                Face::B => ([last, row, last - col], 0),
+                Face::R => ([col, row, last], 2),
+                Face::L => ([last - col, row, 0], 2),
+            };
+            self.cells[x][y][z].stickers[axis].expect("outer cells have stickers facing out")
+        };
+
+        (0..self.size)
+            .map(|row| (0..self.size).map(|col| sticker(row, col)).collect())
+            .collect()
+    }
+
+    /// Turns the layer at `index` along `axis` by `quarters` clockwise
+    /// quarter turns, as seen from F, U and R respectively: the plane of
+    /// cells is rotated, and each cell's stickers not facing along `axis`
+    /// swap axes.
+    fn turn_layer(&mut self, axis: usize, index: usize, quarters: usize) {
+        let mut plane = match axis {
+            0 => get_x_y_plane(&self.cells, index),
+            1 => get_x_z_plane(&self.cells, index),
+            _ => get_y_z_plane(&self.cells, index),
+        };
+
+        match (axis, quarters % 4) {
+            (_, 0) => return,
+            (_, 2) => rotate_180(&mut plane),
+            (0, 1) | (1 | 2, 3) => rotate90(&mut plane),
+            _ => rotate_minus90(&mut plane),
+        }
+
+        if quarters % 2 == 1 {
+            let (a, b) = match axis {
+                0 => (1, 2),
+                1 => (0, 2),
+                _ => (0, 1),
+            };
+            plane.iter_mut().flatten().for_each(|cell| cell.stickers.swap(a, b));
+        }
+
+        match axis {
+            0 => set_x_y_plane(&mut self.cells, &plane, index),
+            1 => set_x_z_plane(&mut self.cells, &plane, index),
+            _ => set_y_z_plane(&mut self.cells, &plane, index),
+        }
+    }
+
+    pub fn turn(&mut self, m: LayerMove) {
+        let (face, layers) = m.block(self.size);
+        assert!(m.fits(self.size), "{} does not turn the layers a {}x{}x{} has", m, self.size, self.size, self.size);
+
+        let last = self.size - 1;
+        let (axis, index, quarters): (usize, fn(usize, usize) -> usize, usize) = match face {
+            Face::F => (0, |_, layer| layer - 1, 1),
+            Face::B => (0, |last, layer| last + 1 - layer, 3),
+            Face::U => (1, |_, layer| layer - 1, 1),
+            Face::D => (1, |last, layer| last + 1 - layer, 3),
+            Face::L => (2, |_, layer| layer - 1, 3),
+            Face::R => (2, |last, layer| last + 1 - layer, 1),
+        };
+
+        for layer in layers {
+            self.turn_layer(axis, index(last, layer), quarters * m.turn().quarters());
+        }
+    }
+
+    pub fn apply(&mut self, m: Move) {
+        self.turn(LayerMove::Standard(m));
+    }
+
+    /// Whether every face is one colour, whichever way round the cube is
+    /// held. Centre pieces of one colour can have swapped places, and on
+    /// cubes with no fixed centres the whole cube can have turned.
+    pub fn looks_solved(&self) -> bool {
+        Face::ALL.into_iter().all(|face| {
+            let stickers = self.face(face);
+            stickers.iter().flatten().all(|&colour| colour == stickers[0][0])
+        })
+    }
+
+    /// The cycles the stickers make: each is the places stickers are in, as
+    /// the face and the colour there on a solved cube, in the order the
+    /// sticker in each moves to the place before.
+    fn sticker_cycles(&self) -> Vec<Vec<(Face, Colour)>> {
+        let size = self.size;
+        let cell_of = |position: [usize; 3]| (position[0] * size + position[1]) * size + position[2];
+        let face_of = |position: [usize; 3], axis: usize| match (axis, position[axis] == 0) {
+            (0, true) => Face::F,
+            (0, false) => Face::B,
+            (1, true) => Face::U,
+            (1, false) => Face::D,
+            (2, true) => Face::L,
+            _ => Face::R,
+        };
+
+        // The sticker each sticker came from, both numbered by cell and
+        // axis.
+        let mut from: Vec<Option<usize>> = vec![None; size * size * size * 3];
+        for (x, square) in self.cells.iter().enumerate() {
+            for (y, line) in square.iter().enumerate() {
+                for (z, cell) in line.iter().enumerate() {
+                    let home = cell.home.map(|index| index as usize);
+                    let solved = Cell::solved(home, size);
+
+                    for (axis, sticker) in cell.stickers.iter().enumerate() {
+                        if sticker.is_some() {
+                            let axis_home = solved.stickers.iter().position(|s| s == sticker).unwrap();
+                            from[cell_of([x, y, z]) * 3 + axis] = Some(cell_of(home) * 3 + axis_home);
+                        }
+                    }
+                }
+            }
+        }
+
+        let position_of = |sticker: usize| {
+            let cell = sticker / 3;
+            ([cell / (size * size), cell / size % size, cell % size], sticker % 3)
+        };
+        let mut seen = vec![false; from.len()];
+        let mut cycles = vec![];
+
+        for start in 0..from.len() {
+            if seen[start] || from[start].is_none() {
+                continue;
+            }
+
+            let mut cycle = vec![];
+            let mut sticker = start;
+            while !seen[sticker] {
+                seen[sticker] = true;
+                let (position, axis) = position_of(sticker);
+                let colour = Cell::solved(position, size).stickers[axis].unwrap();
+
+                cycle.push((face_of(position, axis), colour));
+                sticker = from[sticker].unwrap();
+            }
+            cycles.push(cycle);
+        }
+
+        cycles
+    }
+
+    /// How many times the moves that made this cube have to be repeated,
+    /// from solved, before every piece is home again.
+    pub fn order(&self) -> u64 {
+        self.sticker_cycles().iter().fold(1, |order, cycle| lcm(order, cycle.len() as u64))
+    }
+
+    /// How many times the moves that made this cube have to be repeated,
+    /// from solved, before it looks solved again. This divides `order`, as
+    /// the repetitions that look solved are closed under adding them up.
+    pub fn visible_order(&self) -> u64 {
+        let cycles = self.sticker_cycles();
+        let order = cycles.iter().fold(1, |order, cycle| lcm(order, cycle.len() as u64));
+
+        (1..=order)
+            .filter(|repeats| order % repeats == 0)
+            .find(|&repeats| {
+                let mut colours: Vec<Option<Colour>> = vec![None; 6];
+
+                cycles.iter().all(|cycle| {
+                    cycle.iter().enumerate().all(|(i, &(face, _))| {
+                        let (_, colour) = cycle[(i + repeats as usize) % cycle.len()];
+                        *colours[face as usize].get_or_insert(colour) == colour
+                    })
+                })
+            })
+            .unwrap_or(order)
+    }
+}
+
+/// Solved when every piece is back where it belongs, the same way round.
+impl RubiksCube for RubiksCubeNxN {
+    fn solved(&self) -> bool {
+        self.cells
+            .iter()
+            .enumerate()
+            .flat_map(|(x, square)| square.iter().enumerate().map(move |(y, line)| (x, y, line)))
+            .flat_map(|(x, y, line)| line.iter().enumerate().map(move |(z, cell)| ([x, y, z], cell)))
+            .all(|(position, cell)| *cell == Cell::solved(position, self.size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::Algorithm;
+    use crate::facelet::FaceletCube;
+
+    use super::*;
+
+    fn turned(size: usize, moves: &str) -> RubiksCubeNxN {
+        let mut rc = RubiksCubeNxN::new(size);
+        for token in moves.split_whitespace() {
+            rc.turn(token.parse().unwrap());
+        }
+        rc
+    }
+
+    #[test]
+    fn new_cube_is_solved() {
+        for size in SIZES {
+            assert!(RubiksCubeNxN::new(size).solved());
+        }
+    }
+
+    #[test]
+    fn three_by_three_matches_facelet_cube() {
+        let algorithm: Algorithm = "R U' F2 D L' B M E2 S' Rw Uw' Fw2 Dw Lw' Bw x y' z2".parse().unwrap();
+        let mut rc = RubiksCubeNxN::new(3);
+        let mut fc = FaceletCube::new();
+
+        for &m in algorithm.iter() {
+            rc.apply(m);
+            fc.apply(m);
+
+            for face in Face::ALL {
+                let expected: Vec<Vec<Colour>> = fc.face(face).iter().map(|row| row.to_vec()).collect();
+                assert_eq!(expected, rc.face(face), "{:?} after {}", face, m);
+            }
+        }
+    }
+
+    #[test]
+    fn every_single_layer_turn_solves_if_repeated_four_times() {
+        for size in SIZES {
+            for m in LayerMove::single_layers(size) {
+                let mut rc = RubiksCubeNxN::new(size);
+
+                rc.turn(m);
+                assert!(!rc.solved(), "{} on {}x{}x{}", m, size, size, size);
+
+                for _ in 0..3 {
+                    rc.turn(m);
+                }
+                assert!(rc.solved(), "{} on {}x{}x{}", m, size, size, size);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_move() {
+        let mut rc = turned(5, "2R 3Uw' 2-3Fw2 M r");
+        for token in "2R 3Uw' 2-3Fw2 M r".split_whitespace().rev() {
+            rc.turn(token.parse::<LayerMove>().unwrap().inverse());
+        }
+
+        assert!(rc.solved());
+    }
+
+    #[test]
+    fn blocks_turn_each_of_their_layers() {
+        assert_eq!(turned(4, "R 2R"), turned(4, "Rw"));
+        assert_eq!(turned(5, "R 2R 3R"), turned(5, "3Rw"));
+        assert_eq!(turned(5, "2L 3L 4L"), turned(5, "M"));
+        assert_eq!(turned(5, "2-4Lw"), turned(5, "M"));
+        assert_eq!(turned(4, "4Rw"), turned(4, "x"));
+        assert_eq!(turned(6, "R 2-5Rw' L'"), turned(6, "R M L'"));
+    }
+
+    #[test]
+    fn rotation_leaves_every_piece_somewhere_else() {
+        let rc = turned(4, "y");
+
+        assert!(!rc.solved());
+        assert_eq!(vec![vec![Face::R.colour(); 4]; 4], rc.face(Face::F));
+    }
+
+    #[test]
+    fn display_writes_big_cube_notation() {
+        for text in ["R", "2R", "3Rw'", "2-3Uw2", "M'", "x2"] {
+            assert_eq!(text, text.parse::<LayerMove>().unwrap().to_string());
+        }
+        assert_eq!("3Rw", "3r".parse::<LayerMove>().unwrap().to_string());
+        assert_eq!("Rw", "r".parse::<LayerMove>().unwrap().to_string());
+    }
+
+    #[test]
+    fn from_str_rejects_unknown_blocks() {
+        for text in ["0R", "3-2Rw", "2-3R", "2M", "2x", "R3", ""] {
+            assert!(text.parse::<LayerMove>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn single_layers_counts_each_slice_once() {
+        assert_eq!(18, LayerMove::single_layers(2).len());
+        assert_eq!(27, LayerMove::single_layers(3).len());
+        assert_eq!(36, LayerMove::single_layers(4).len());
+        assert_eq!(63, LayerMove::single_layers(7).len());
+        assert!(LayerMove::single_layers(3).contains(&LayerMove::Standard(Move::EPrime)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_deep_a_block_panics() {
+        turned(3, "4Rw");
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_with_no_middle_layer_panics() {
+        turned(2, "M");
+    }
+
+    #[test]
+    fn moves_fit_cubes_with_the_layers_they_turn() {
+        let fits = |m: &str, size: usize| m.parse::<LayerMove>().unwrap().fits(size);
+
+        assert_eq!((false, true, true), (fits("E", 2), fits("E", 3), fits("E", 4)));
+        assert_eq!((false, true), (fits("4Rw", 3), fits("4Rw", 4)));
+        assert_eq!((true, true), (fits("R", 2), fits("x", 2)));
+    }
+
+    /// How many times `moves` are done before `solved` holds, done one
+    /// repetition after another.
+    fn repetitions(size: usize, moves: &str, solved: fn(&RubiksCubeNxN) -> bool) -> u64 {
+        let once = turned(size, moves);
+        let mut rc = once.clone();
+        let mut count = 1;
+
+        while !solved(&rc) {
+            for token in moves.split_whitespace() {
+                rc.turn(token.parse().unwrap());
+            }
+            count += 1;
+        }
+
+        count
+    }
+
+    #[test]
+    fn orders_count_repetitions_until_solved() {
+        let cases = [
+            (3, "R U"), (4, "R U"), (4, "Rw U2 x"), (5, "2R U' F"),
+            (4, "Rw' U2 Rw U2"), (5, "M' U M U2"), (4, "Rw U2 Rw U2 Rw U2"),
+        ];
+
+        for (size, moves) in cases {
+            let rc = turned(size, moves);
+
+            assert_eq!(repetitions(size, moves, |rc| rc.solved()), rc.order(), "{} on {}", moves, size);
+            assert_eq!(repetitions(size, moves, RubiksCubeNxN::looks_solved), rc.visible_order(), "{} on {}", moves, size);
+        }
+    }
+
+    #[test]
+    fn three_by_three_order_matches_cycles() {
+        for algorithm in ["R U", "R U R' U'", "M' U M U2 M' U M", "Rw U x'", "F R2 D' B L"] {
+            let mut rc = RubiksCube3x3::new();
+            algorithm.parse::<Algorithm>().unwrap().apply(&mut rc);
+
+            assert_eq!(crate::cycles::order(&rc), turned(3, algorithm).order(), "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn rotations_look_solved_at_once() {
+        let rc = turned(4, "y");
+
+        assert_eq!(true, rc.looks_solved());
+        assert_eq!(1, rc.visible_order());
+        assert_eq!(4, rc.order());
+    }
+
+    #[test]
+    fn swapped_centres_look_solved_before_every_piece_is_home() {
+        for size in [4, 5] {
+            let rc = turned(size, "Rw U2 Rw U2 Rw U2");
+
+            assert_eq!(10, rc.visible_order());
+            assert_eq!(20, rc.order());
+        }
+    }
+
+    use test::Bencher;
+
+    #[bench]
+    fn bench_wide_turn_7x7(b: &mut Bencher) {
+        let mut rc = RubiksCubeNxN::new(7);
+        let m = "3Rw".parse().unwrap();
+
+        b.iter(|| rc.turn(m));
+    }
+}
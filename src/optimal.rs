@@ -0,0 +1,190 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::algorithm::Algorithm;
+use crate::moves::*;
+use crate::pattern::{PatternDatabase, FACE_TURNS};
+use crate::rubiks::*;
+use crate::search::CanonicalSequences;
+
+/// No position of the cube needs more face turns than this.
+pub const GODS_NUMBER: usize = 20;
+
+/// The whole cube rotations, shortest first, starting with doing nothing.
+pub(crate) fn rotations() -> Vec<Algorithm> {
+    let mut found: Vec<(RubiksCube3x3, Algorithm)> = vec![(RubiksCube3x3::new(), Algorithm::new())];
+    let mut next = 0;
+
+    while next < found.len() {
+        let (rc, rotation) = found[next].clone();
+        next += 1;
+
+        for m in [Move::X, Move::X2, Move::XPrime, Move::Y, Move::Y2, Move::YPrime, Move::Z, Move::Z2, Move::ZPrime] {
+            let mut turned = rc;
+            turned.apply(m);
+
+            if !found.iter().any(|(seen, _)| seen.centres == turned.centres) {
+                let mut longer = rotation.clone();
+                longer.0.push(m);
+                found.push((turned, longer));
+            }
+        }
+    }
+
+    found.into_iter().map(|(_, rotation)| rotation).collect()
+}
+
+/// `rc` turned as a whole so its centres are where they belong, with the
+/// rotation that does it. Face turns alone can then solve it.
+pub(crate) fn upright(rc: &RubiksCube3x3) -> (Algorithm, RubiksCube3x3) {
+    rotations()
+        .into_iter()
+        .map(|rotation| {
+            let mut turned = *rc;
+            rotation.apply(&mut turned);
+            (rotation, turned)
+        })
+        .find(|(_, turned)| turned.centres == Face::ALL)
+        .expect("some rotation puts the centres back")
+}
+
+/// Finds solutions with the fewest face turns, counting half turns as one,
+/// by iterative deepening A*: depth first searches that give up on a branch
+/// as soon as the pattern databases show it cannot finish within the bound,
+/// with the bound raised a turn at a time until a solution turns up.
+pub struct OptimalSolver {
+    databases: Vec<PatternDatabase>,
+    face_turns: CanonicalSequences,
+}
+
+impl OptimalSolver {
+    pub fn new(databases: Vec<PatternDatabase>) -> Self {
+        OptimalSolver { databases, face_turns: CanonicalSequences::new(FACE_TURNS) }
+    }
+
+    /// A solver using every corner, and the first and last six edges, as
+    /// Korf did. The databases are kept in `tables`, and worked out and saved
+    /// there the first time, which takes some minutes.
+    pub fn standard(tables: &Path) -> io::Result<Self> {
+        fs::create_dir_all(tables)?;
+
+        let (first, last) = Edge::ALL.split_at(6);
+        let databases = vec![
+            PatternDatabase::load_or_new(&tables.join("corners.pdb"), &Corner::ALL, &[])?,
+            PatternDatabase::load_or_new(&tables.join("edges-first.pdb"), &[], first)?,
+            PatternDatabase::load_or_new(&tables.join("edges-last.pdb"), &[], last)?,
+        ];
+
+        Ok(OptimalSolver::new(databases))
+    }
+
+    /// The fewest face turns `rc` could possibly be solved in.
+    fn lower_bound(&self, rc: &RubiksCube3x3) -> usize {
+        self.databases.iter().map(|database| database.distance(rc) as usize).max().unwrap_or(0)
+    }
+
+    /// A shortest sequence of face turns that solves `rc`, after a rotation
+    /// if slice moves have left its centres out of place. `None` if there is
+    /// none, which only happens to cubes that were put together wrong.
+    pub fn solve(&self, rc: &RubiksCube3x3) -> Option<Algorithm> {
+        let (mut solution, rc) = upright(rc);
+        let mut moves = vec![];
+
+        (self.lower_bound(&rc)..=GODS_NUMBER).find(|&bound| self.search(&rc, bound, &mut moves))?;
+
+        solution.0.extend(moves);
+        Some(solution)
+    }
+
+    /// Whether `rc` can be solved in `bound` more face turns after `moves`,
+    /// leaving the turns that do it on the end of `moves` if so.
+    fn search(&self, rc: &RubiksCube3x3, bound: usize, moves: &mut Vec<Move>) -> bool {
+        if rc.solved() {
+            return true;
+        }
+        if self.lower_bound(rc) > bound {
+            return false;
+        }
+
+        for &m in self.face_turns.pool() {
+            if !self.face_turns.allows(moves, m) {
+                continue;
+            }
+
+            let mut turned = *rc;
+            turned.apply(m);
+            moves.push(m);
+
+            if bound > 0 && self.search(&turned, bound - 1, moves) {
+                return true;
+            }
+            moves.pop();
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use super::*;
+
+    /// A solver small enough to build for every test run: its databases only
+    /// follow the U layer.
+    fn solver() -> &'static OptimalSolver {
+        static SOLVER: OnceLock<OptimalSolver> = OnceLock::new();
+
+        SOLVER.get_or_init(|| OptimalSolver::new(vec![
+            PatternDatabase::new(&[Corner::URF, Corner::UFL, Corner::ULB, Corner::UBR], &[]),
+            PatternDatabase::new(&[], &[Edge::UR, Edge::UF, Edge::UL, Edge::UB]),
+        ]))
+    }
+
+    fn solution(algorithm: &str) -> Algorithm {
+        let mut rc = RubiksCube3x3::new();
+        algorithm.parse::<Algorithm>().unwrap().apply(&mut rc);
+
+        let solution = solver().solve(&rc).unwrap();
+        solution.apply(&mut rc);
+        assert!(rc.solved(), "{} does not solve {}", solution, algorithm);
+
+        solution
+    }
+
+    fn face_turns(algorithm: &Algorithm) -> usize {
+        algorithm.iter().filter(|m| m.layer().kind() == LayerKind::Face).count()
+    }
+
+    #[test]
+    fn solved_cube_needs_no_moves() {
+        assert_eq!(Algorithm::new(), solution(""));
+    }
+
+    #[test]
+    fn solutions_are_as_short_as_possible() {
+        assert_eq!("U' R'", solution("R U").to_string());
+        assert_eq!(4, solution("R U R' U'").len());
+        assert_eq!(5, solution("F R2 D' B L").len());
+        assert_eq!(2, solution("R U U' R' F2 D").len());
+    }
+
+    #[test]
+    fn slice_moves_are_solved_after_a_rotation() {
+        let solution = solution("M");
+
+        assert_eq!(2, face_turns(&solution));
+        assert_eq!(1, solution.len() - face_turns(&solution));
+    }
+
+    #[test]
+    fn upright_puts_centres_back() {
+        let mut rc = RubiksCube3x3::new();
+        "M E' S2".parse::<Algorithm>().unwrap().apply(&mut rc);
+
+        assert_eq!(Face::ALL, upright(&rc).1.centres);
+        assert_eq!(24, rotations().len());
+    }
+}
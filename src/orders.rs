@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use crate::cycles::{cycle_order, lcm, CycleType};
+
+/// The cycle types of the cube with its centres fixed that have one order.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Order {
+    pub order: u64,
+    /// How many combinations of corner and edge cycle types have the order.
+    pub cycle_types: u64,
+    /// One of them, as its corner and edge cycle types.
+    pub example: (CycleType, CycleType),
+}
+
+/// What a cycle type of one kind of piece contributes to a whole position.
+struct Shape {
+    cycle_type: CycleType,
+    order: u64,
+    odd: bool,
+}
+
+/// Every cycle type of `pieces` pieces with `modulus` orientations whose
+/// twists add up to a whole turn, as the cube needs of both corners and
+/// edges. Pieces standing still untwisted are left out of the cycle type,
+/// as `CycleType::of` does.
+fn shapes(pieces: usize, modulus: u8) -> Vec<Shape> {
+    fn extend(left: usize,
+              modulus: u8,
+              cycles: &mut Vec<(usize, u8)>,
+              shapes: &mut Vec<Shape>) {
+        if left == 0 {
+            let twist: usize = cycles.iter().map(|&(_, twist)| twist as usize).sum();
+            if !twist.is_multiple_of(modulus as usize) {
+                return;
+            }
+
+            let order = cycles.iter().fold(1, |order, &(length, twist)| lcm(order, cycle_order(length, twist, modulus)));
+            let odd = cycles.iter().map(|&(length, _)| length - 1).sum::<usize>() % 2 == 1;
+            let cycle_type = CycleType(cycles.iter().copied().filter(|&cycle| cycle != (1, 0)).collect());
+
+            shapes.push(Shape { cycle_type, order, odd });
+            return;
+        }
+
+        // Cycles come longest first, and cycles of one length least twisted
+        // first, so each cycle type is made once.
+        let (longest, least_twist) = cycles.last().copied().unwrap_or((left, 0));
+
+        for length in (1..=longest.min(left)).rev() {
+            let first_twist = if length == longest { least_twist } else { 0 };
+
+            for twist in first_twist..modulus {
+                cycles.push((length, twist));
+                extend(left - length, modulus, cycles, shapes);
+                cycles.pop();
+            }
+        }
+    }
+
+    let mut shapes = vec![];
+    extend(pieces, modulus, &mut vec![], &mut shapes);
+    shapes
+}
+
+/// Every order an element of the cube group has, with how many cycle types
+/// give it, smallest first.
+///
+/// A position can be reached with face turns exactly when its corner and
+/// edge permutations are both even or both odd, its corner twists add up to
+/// a whole turn and its edge flips to an even number. So every pair of a
+/// corner and an edge cycle type meeting those is reached by something, and
+/// its order is the least common multiple of theirs.
+pub fn achievable_orders() -> Vec<Order> {
+    let corners = shapes(8, 3);
+    let edges = shapes(12, 2);
+    let mut orders: BTreeMap<u64, Order> = BTreeMap::new();
+
+    for corner in &corners {
+        for edge in edges.iter().filter(|edge| edge.odd == corner.odd) {
+            let order = lcm(corner.order, edge.order);
+
+            orders
+                .entry(order)
+                .or_insert_with(|| Order {
+                    order,
+                    cycle_types: 0,
+                    example: (corner.cycle_type.clone(), edge.cycle_type.clone()),
+                })
+                .cycle_types += 1;
+        }
+    }
+
+    orders.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cycles;
+    use crate::moves::*;
+    use crate::rubiks::RubiksCube3x3;
+    use crate::search::CanonicalSequences;
+
+    #[test]
+    fn cube_group_has_73_orders_up_to_1260() {
+        let orders = achievable_orders();
+
+        assert_eq!(73, orders.len());
+        assert_eq!(1, orders[0].order);
+        assert_eq!(1260, orders.last().unwrap().order);
+    }
+
+    #[test]
+    fn solved_is_the_only_cycle_type_of_order_one() {
+        let orders = achievable_orders();
+
+        assert_eq!(1, orders[0].cycle_types);
+        assert_eq!((CycleType::default(), CycleType::default()), orders[0].example);
+    }
+
+    #[test]
+    fn shapes_keep_twists_that_add_up_to_a_whole_turn() {
+        let corners = shapes(8, 3);
+
+        assert!(corners.iter().any(|shape| shape.cycle_type == CycleType(vec![(1, 1), (1, 2)])));
+        assert!(!corners.iter().any(|shape| shape.cycle_type == CycleType(vec![(1, 1)])));
+        assert!(!shapes(12, 2).iter().any(|shape| shape.cycle_type == CycleType(vec![(1, 1)])));
+    }
+
+    #[test]
+    fn face_turn_sequences_only_reach_achievable_orders() {
+        let orders: Vec<u64> = achievable_orders().iter().map(|order| order.order).collect();
+        let pool: Vec<Move> = Move::ALL
+            .into_iter()
+            .filter(|m| m.layer().kind() == LayerKind::Face)
+            .collect();
+
+        CanonicalSequences::new(&pool).for_each(3, |sequence| {
+            let mut rc = RubiksCube3x3::new();
+            sequence.iter().for_each(|&m| rc.apply(m));
+
+            assert!(orders.contains(&cycles::order(&rc)));
+        });
+    }
+}
@@ -0,0 +1,396 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::moves::Move;
+use crate::rubiks::*;
+
+/// The 18 turns of the six faces, the moves optimal solutions are counted in.
+pub const FACE_TURNS: &[Move] = Move::ALL.split_at(18).0;
+
+/// Marks an entry the breadth first search has not reached yet.
+const UNSEEN: u8 = 0xF;
+
+/// Marks the start of a saved pattern database, and which version it is.
+const MAGIC: &[u8; 4] = b"RCP1";
+
+/// Where one kind of piece goes under each face turn: `to[m][p]` is the
+/// position the piece at position `p` moves to, and `turn[m][p]` how much
+/// more it is twisted or flipped once there.
+struct PieceMoves {
+    to: Vec<[u8; 12]>,
+    turn: Vec<[u8; 12]>,
+}
+
+impl PieceMoves {
+    fn new(positions: impl Fn(&RubiksCube3x3) -> Vec<(usize, u8)>) -> Self {
+        let mut to = vec![[0; 12]; FACE_TURNS.len()];
+        let mut turn = vec![[0; 12]; FACE_TURNS.len()];
+
+        for (i, &m) in FACE_TURNS.iter().enumerate() {
+            let mut rc = RubiksCube3x3::new();
+            rc.apply(m);
+
+            // Position `p` now holds the piece that was at position `from`.
+            for (p, (from, orientation)) in positions(&rc).into_iter().enumerate() {
+                to[i][from] = p as u8;
+                turn[i][from] = orientation;
+            }
+        }
+
+        PieceMoves { to, turn }
+    }
+}
+
+/// Some of one kind of piece, as followed by a pattern database: which
+/// pieces, out of how many, and how many ways each can be turned.
+struct Pieces {
+    tracked: Vec<usize>,
+    /// Which of `tracked` each piece is, if it is tracked at all.
+    slot: [Option<u8>; 12],
+    positions: usize,
+    modulus: u8,
+    moves: PieceMoves,
+}
+
+impl Pieces {
+    fn new(tracked: Vec<usize>, positions: usize, modulus: u8, moves: PieceMoves) -> Self {
+        let mut slot = [None; 12];
+        for (i, &piece) in tracked.iter().enumerate() {
+            slot[piece] = Some(i as u8);
+        }
+
+        Pieces { tracked, slot, positions, modulus, moves }
+    }
+
+    /// Once every piece is tracked the last orientation follows from the
+    /// others, so it is left out of the index.
+    fn orientations(&self) -> usize {
+        if self.tracked.len() == self.positions { self.tracked.len() - 1 } else { self.tracked.len() }
+    }
+
+    /// How many arrangements of the tracked pieces there are.
+    fn size(&self) -> usize {
+        let arrangements: usize = (self.positions - self.tracked.len() + 1..=self.positions).product();
+        arrangements * (self.modulus as usize).pow(self.orientations() as u32)
+    }
+
+    /// Where each tracked piece is and how it is turned, from what sits at
+    /// each position.
+    fn locate(&self, pieces: &[(usize, u8)], state: &mut [(u8, u8)]) {
+        for (position, &(piece, orientation)) in pieces.iter().enumerate() {
+            if let Some(slot) = self.slot[piece] {
+                state[slot as usize] = (position as u8, orientation);
+            }
+        }
+    }
+
+    fn rank(&self, state: &[(u8, u8)]) -> usize {
+        let mut used = 0u32;
+        let mut arrangement = 0;
+
+        for (i, &(position, _)) in state.iter().enumerate() {
+            let smaller_free = (!used & ((1 << position) - 1)).count_ones() as usize;
+            arrangement = arrangement * (self.positions - i) + smaller_free;
+            used |= 1 << position;
+        }
+
+        state[..self.orientations()]
+            .iter()
+            .fold(arrangement, |index, &(_, orientation)| index * self.modulus as usize + orientation as usize)
+    }
+
+    fn unrank(&self, mut index: usize, state: &mut [(u8, u8)]) {
+        let modulus = self.modulus as usize;
+        let mut total = 0;
+
+        for i in (0..self.orientations()).rev() {
+            state[i].1 = (index % modulus) as u8;
+            total += index % modulus;
+            index /= modulus;
+        }
+        if self.orientations() < self.tracked.len() {
+            state[self.tracked.len() - 1].1 = ((modulus - total % modulus) % modulus) as u8;
+        }
+
+        let mut smaller_free = vec![0; self.tracked.len()];
+        for i in (0..self.tracked.len()).rev() {
+            let base = self.positions - i;
+            smaller_free[i] = index % base;
+            index /= base;
+        }
+
+        let mut used = 0u32;
+        for (i, &skip) in smaller_free.iter().enumerate() {
+            let position = (0..self.positions as u8)
+                .filter(|&p| used & (1 << p) == 0)
+                .nth(skip)
+                .unwrap();
+            state[i].0 = position;
+            used |= 1 << position;
+        }
+    }
+
+    fn apply(&self, m: usize, state: &mut [(u8, u8)]) {
+        for (position, orientation) in state.iter_mut() {
+            let from = *position as usize;
+            *position = self.moves.to[m][from];
+            *orientation = (*orientation + self.moves.turn[m][from]) % self.modulus;
+        }
+    }
+}
+
+fn corners(rc: &RubiksCube3x3) -> Vec<(usize, u8)> {
+    (0..8).map(|i| (rc.corners[i] as usize, rc.twists[i])).collect()
+}
+
+fn edges(rc: &RubiksCube3x3) -> Vec<(usize, u8)> {
+    (0..12).map(|i| (rc.edges[i] as usize, rc.flips[i])).collect()
+}
+
+/// How many face turns it takes, at least, to put some of the corners and
+/// edges back where they belong, for every way they can be arranged.
+///
+/// No position can be solved in fewer turns than its pieces need, so these
+/// make a heuristic for IDA* that never overestimates. Distances are found
+/// by a breadth first search from solved, and kept four bits to an entry.
+pub struct PatternDatabase {
+    corners: Pieces,
+    edges: Pieces,
+    distances: Vec<u8>,
+}
+
+impl PatternDatabase {
+    /// Works out the database following `corners` and `edges` from scratch.
+    /// With every corner, or six edges, this takes a while.
+    pub fn new(corners: &[Corner], edges: &[Edge]) -> Self {
+        let mut database = PatternDatabase::empty(corners, edges);
+        database.search();
+        database
+    }
+
+    fn empty(tracked_corners: &[Corner], tracked_edges: &[Edge]) -> Self {
+        let corners = Pieces::new(tracked_corners.iter().map(|&c| c as usize).collect(),
+                                  8,
+                                  3,
+                                  PieceMoves::new(corners));
+        let edges = Pieces::new(tracked_edges.iter().map(|&e| e as usize).collect(),
+                                12,
+                                2,
+                                PieceMoves::new(edges));
+        let entries = corners.size() * edges.size();
+
+        PatternDatabase { corners, edges, distances: vec![UNSEEN << 4 | UNSEEN; entries.div_ceil(2)] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.corners.size() * self.edges.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, index: usize) -> u8 {
+        (self.distances[index / 2] >> (index % 2 * 4)) & 0xF
+    }
+
+    fn set(&mut self, index: usize, distance: u8) {
+        let shift = index % 2 * 4;
+        let byte = &mut self.distances[index / 2];
+        *byte = (*byte & !(0xF << shift)) | distance << shift;
+    }
+
+    /// Fills in every entry a depth at a time, expanding the entries found
+    /// at the last depth.
+    fn search(&mut self) {
+        let corner_count = self.corners.tracked.len();
+        let edge_count = self.edges.tracked.len();
+        let edge_size = self.edges.size();
+        let mut state = vec![(0, 0); corner_count + edge_count];
+        let mut moved = state.clone();
+
+        self.corners.locate(&corners(&RubiksCube3x3::new()), &mut state[..corner_count]);
+        self.edges.locate(&edges(&RubiksCube3x3::new()), &mut state[corner_count..]);
+        let solved = self.index(&state);
+        self.set(solved, 0);
+
+        let mut found = 1;
+        let mut depth = 0;
+
+        while found > 0 {
+            found = 0;
+
+            for index in 0..self.len() {
+                if self.get(index) != depth {
+                    continue;
+                }
+
+                self.corners.unrank(index / edge_size, &mut state[..corner_count]);
+                self.edges.unrank(index % edge_size, &mut state[corner_count..]);
+
+                for m in 0..FACE_TURNS.len() {
+                    moved.copy_from_slice(&state);
+                    self.corners.apply(m, &mut moved[..corner_count]);
+                    self.edges.apply(m, &mut moved[corner_count..]);
+
+                    let next = self.index(&moved);
+                    if self.get(next) == UNSEEN {
+                        self.set(next, depth + 1);
+                        found += 1;
+                    }
+                }
+            }
+
+            depth += 1;
+        }
+    }
+
+    fn index(&self, state: &[(u8, u8)]) -> usize {
+        let (corners, edges) = state.split_at(self.corners.tracked.len());
+        self.corners.rank(corners) * self.edges.size() + self.edges.rank(edges)
+    }
+
+    /// The fewest face turns that put the followed pieces of `rc` back where
+    /// they belong. `rc` must have its centres where they belong.
+    pub fn distance(&self, rc: &RubiksCube3x3) -> u8 {
+        let mut state = [(0, 0); 20];
+        let corner_count = self.corners.tracked.len();
+        let state = &mut state[..corner_count + self.edges.tracked.len()];
+
+        self.corners.locate(&corners(rc), &mut state[..corner_count]);
+        self.edges.locate(&edges(rc), &mut state[corner_count..]);
+
+        self.get(self.index(state))
+    }
+
+    /// Writes the database to `path`: `MAGIC`, the number of corners and
+    /// their indices, the number of edges and theirs, then the distances.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut bytes = MAGIC.to_vec();
+
+        for pieces in [&self.corners, &self.edges] {
+            bytes.push(pieces.tracked.len() as u8);
+            bytes.extend(pieces.tracked.iter().map(|&piece| piece as u8));
+        }
+        bytes.extend(&self.distances);
+
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, path)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+        let bytes = fs::read(path)?;
+        let rest = bytes.strip_prefix(MAGIC).ok_or_else(|| invalid("not a pattern database"))?;
+
+        let pieces = |rest: &mut &[u8], count: usize| -> io::Result<Vec<usize>> {
+            let (&length, tail) = rest.split_first().ok_or_else(|| invalid("pattern database cut short"))?;
+            let length = length as usize;
+            let tracked = tail.get(..length).ok_or_else(|| invalid("pattern database cut short"))?;
+
+            if tracked.iter().any(|&piece| piece as usize >= count) {
+                return Err(invalid("not a piece"));
+            }
+
+            *rest = &tail[length..];
+            Ok(tracked.iter().map(|&piece| piece as usize).collect())
+        };
+
+        let mut rest = rest;
+        let tracked_corners: Vec<Corner> = pieces(&mut rest, 8)?.into_iter().map(|c| Corner::ALL[c]).collect();
+        let tracked_edges: Vec<Edge> = pieces(&mut rest, 12)?.into_iter().map(|e| Edge::ALL[e]).collect();
+
+        let mut database = PatternDatabase::empty(&tracked_corners, &tracked_edges);
+        if rest.len() != database.distances.len() {
+            return Err(invalid("pattern database is the wrong size"));
+        }
+        database.distances.copy_from_slice(rest);
+
+        Ok(database)
+    }
+
+    /// The database saved at `path`, or if there is none yet, a new one,
+    /// saved there for next time.
+    pub fn load_or_new(path: &Path, corners: &[Corner], edges: &[Edge]) -> io::Result<Self> {
+        match PatternDatabase::load(path) {
+            Ok(database) => Ok(database),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                let database = PatternDatabase::new(corners, edges);
+                database.save(path)?;
+                Ok(database)
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::Algorithm;
+
+    fn scrambled(algorithm: &str) -> RubiksCube3x3 {
+        let mut rc = RubiksCube3x3::new();
+        algorithm.parse::<Algorithm>().unwrap().apply(&mut rc);
+        rc
+    }
+
+    fn u_layer() -> PatternDatabase {
+        PatternDatabase::new(&[Corner::URF, Corner::UFL, Corner::ULB, Corner::UBR], &[])
+    }
+
+    #[test]
+    fn rank_then_unrank_gives_same_state() {
+        let every_corner = Pieces::new((0..8).collect(), 8, 3, PieceMoves::new(corners));
+        let some_edges = Pieces::new(vec![0, 7, 10], 12, 2, PieceMoves::new(edges));
+        let mut state = [(0, 0); 8];
+
+        for index in [0, 1, 12345, every_corner.size() - 1] {
+            every_corner.unrank(index, &mut state);
+            assert_eq!(index, every_corner.rank(&state));
+        }
+        for index in [0, 7, some_edges.size() - 1] {
+            some_edges.unrank(index, &mut state[..3]);
+            assert_eq!(index, some_edges.rank(&state[..3]));
+        }
+        assert_eq!(88179840, every_corner.size());
+    }
+
+    #[test]
+    fn distances_count_face_turns_for_followed_pieces() {
+        let database = u_layer();
+
+        assert_eq!(0, database.distance(&RubiksCube3x3::new()));
+        assert_eq!(0, database.distance(&scrambled("D")));
+        assert_eq!(1, database.distance(&scrambled("R")));
+        assert_eq!(2, database.distance(&scrambled("R U")));
+        assert!(database.distance(&scrambled("R U R' U'")) <= 4);
+        assert_eq!(136080, database.len());
+    }
+
+    #[test]
+    fn every_entry_is_reached() {
+        let database = PatternDatabase::new(&[Corner::URF, Corner::DRB], &[]);
+
+        assert!((0..database.len()).all(|index| database.get(index) != UNSEEN));
+    }
+
+    #[test]
+    fn save_then_load_gives_same_distances() {
+        let path = std::env::temp_dir().join(format!("pattern-test-{}", std::process::id()));
+        let database = PatternDatabase::new(&[Corner::UFL, Corner::DBL], &[Edge::FR]);
+
+        database.save(&path).unwrap();
+        let loaded = PatternDatabase::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(database.corners.tracked, loaded.corners.tracked);
+        assert_eq!(database.edges.tracked, loaded.edges.tracked);
+        assert_eq!(database.distances, loaded.distances);
+    }
+}
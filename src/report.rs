@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::sink::Record;
+use crate::statistics::Statistics;
+
+/// A summary of a search for the README: for each depth, the classes with
+/// the longest cycles, and how many sequences take each number of
+/// iterations to get back to solved.
+#[derive(Clone, Debug)]
+pub struct Report {
+    top: usize,
+    longest: BTreeMap<usize, Vec<Record>>,
+    histograms: Statistics,
+}
+
+impl Report {
+    /// A report keeping the `top` longest cycles of each depth.
+    pub fn new(top: usize) -> Self {
+        Report { top, longest: BTreeMap::new(), histograms: Statistics::new() }
+    }
+
+    /// Counts `record` in the histogram of its depth, and keeps it if it is
+    /// one of the longest cycles so far.
+    pub fn add(&mut self, record: &Record) {
+        self.histograms.add(record.depth, record.order, record.size as u64);
+
+        let longest = self.longest.entry(record.depth).or_default();
+        let position = longest.partition_point(|kept| kept.order > record.order
+                                               || (kept.order == record.order && kept.sequence < record.sequence));
+        if position < self.top {
+            longest.insert(position, record.clone());
+            longest.truncate(self.top);
+        }
+    }
+
+    /// The longest cycles of every depth as a markdown table, laid out like
+    /// the one in the README.
+    pub fn longest_table(&self) -> String {
+        let width = self.longest
+            .values()
+            .flatten()
+            .map(|record| record.sequence.to_string().len())
+            .chain([26])
+            .max()
+            .unwrap();
+
+        let mut table = format!("| Depth | Iterations | {:<width$} |\n", "Move List");
+        table += &format!("|------ | ---------- | {} |\n", "-".repeat(width));
+
+        for (depth, records) in &self.longest {
+            for record in records {
+                table += &format!("|{:>6} |{:>11} |{:>w$} |\n",
+                                  depth, record.order, record.sequence.to_string(), w = width + 1);
+            }
+        }
+
+        table
+    }
+
+    /// How many sequences of each depth take each number of iterations, as
+    /// a markdown table.
+    pub fn histogram_table(&self) -> String {
+        let mut table = String::from("| Depth | Iterations |  Sequences |\n");
+        table += "|------ | ---------- | ---------- |\n";
+
+        for (depth, statistics) in self.histograms.depths() {
+            for (order, count) in statistics.histogram().iter().rev() {
+                table += &format!("|{:>6} |{:>11} |{:>11} |\n", depth, order, count);
+            }
+        }
+
+        table
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "{}\nHow many move lists of each depth take each number of iterations:\n\n{}",
+               self.longest_table(),
+               self.histogram_table())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::Algorithm;
+    use crate::cycles;
+    use crate::equivalence::EquivalenceClasses;
+    use crate::rubiks::RubiksCube3x3;
+
+    fn add(report: &mut Report, depth: usize, algorithms: &[&str]) {
+        let mut classes = EquivalenceClasses::new();
+
+        for algorithm in algorithms {
+            let algorithm: Algorithm = algorithm.parse().unwrap();
+            let mut rc = RubiksCube3x3::new();
+            algorithm.apply(&mut rc);
+            classes.add(&algorithm, cycles::order(&rc));
+        }
+
+        for class in classes.classes() {
+            report.add(&Record::new(depth, class));
+        }
+    }
+
+    #[test]
+    fn longest_table_matches_readme_layout() {
+        let mut report = Report::new(2);
+        add(&mut report, 1, &["U", "U'", "R"]);
+        add(&mut report, 2, &["U R", "R U", "U R'", "U D"]);
+
+        assert_eq!("\
+| Depth | Iterations | Move List                  |
+|------ | ---------- | -------------------------- |
+|     1 |          4 |                          U |
+|     2 |        105 |                        U R |
+|     2 |         63 |                       U R' |
+", report.longest_table());
+    }
+
+    #[test]
+    fn histogram_table_counts_every_sequence() {
+        let mut report = Report::new(1);
+        add(&mut report, 2, &["U R", "R U", "U R'", "U D", "U2 D2"]);
+
+        assert_eq!("\
+| Depth | Iterations |  Sequences |
+|------ | ---------- | ---------- |
+|     2 |        105 |          2 |
+|     2 |         63 |          1 |
+|     2 |          4 |          1 |
+|     2 |          2 |          1 |
+", report.histogram_table());
+    }
+
+    #[test]
+    fn report_separates_tables_so_markdown_renders_both() {
+        let mut report = Report::new(1);
+        add(&mut report, 1, &["U"]);
+
+        assert_eq!("\
+| Depth | Iterations | Move List                  |
+|------ | ---------- | -------------------------- |
+|     1 |          4 |                          U |
+
+How many move lists of each depth take each number of iterations:
+
+| Depth | Iterations |  Sequences |
+|------ | ---------- | ---------- |
+|     1 |          4 |          1 |
+", report.to_string());
+    }
+}
@@ -1,187 +1,316 @@
-use crate::transforms::*;
+use std::sync::OnceLock;
 
-#[derive(PartialEq, Clone, Copy, Debug)]
-pub enum RubiksCell {
-    RYB, RY, RYG, RB, R, RG, RBW, RW, RGW,
-    YB, Y, YG, B, C, G, BW, W, GW,
-    YBO, YO, YGO, BO, O, GO, BWO, WO, GWO,
+use crate::moves::{Layer, Move};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Colour {
+    Red, Orange, Yellow, White, Blue, Green,
+}
+
+impl Colour {
+    pub const ALL: [Colour; 6] = [
+        Colour::Red, Colour::Orange, Colour::Yellow, Colour::White, Colour::Blue, Colour::Green,
+    ];
+
+    pub fn letter(self) -> char {
+        match self {
+            Colour::Red => 'R',
+            Colour::Orange => 'O',
+            Colour::Yellow => 'Y',
+            Colour::White => 'W',
+            Colour::Blue => 'B',
+            Colour::Green => 'G',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Colour> {
+        Colour::ALL.into_iter().find(|colour| colour.letter() == letter)
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Face {
+    U, R, F, D, L, B,
 }
 
-type Cube = [[[RubiksCell; 3]; 3]; 3];
-
-impl RubiksCell {
-
-    pub const fn collect() -> Cube {
-        use RubiksCell::*;
-
-        [
-            [
-                [RYB, RY, RYG],
-                [RB, R, RG],
-                [RBW, RW, RGW]
-            ],
-            [
-                [YB, Y, YG],
-                [B, C, G],
-                [BW, W, GW]
-            ],
-            [
-                [YBO, YO, YGO],
-                [BO, O, GO],
-                [BWO, WO, GWO]
-            ],
-        ]
+impl Face {
+    pub const ALL: [Face; 6] = [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+    /// The colour of this face on a solved cube.
+    pub fn colour(self) -> Colour {
+        match self {
+            Face::U => Colour::Yellow,
+            Face::R => Colour::Green,
+            Face::F => Colour::Red,
+            Face::D => Colour::White,
+            Face::L => Colour::Blue,
+            Face::B => Colour::Orange,
+        }
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Corner {
+    URF, UFL, ULB, UBR, DFR, DLF, DBL, DRB,
+}
+
+impl Corner {
+    pub const ALL: [Corner; 8] = [
+        Corner::URF, Corner::UFL, Corner::ULB, Corner::UBR,
+        Corner::DFR, Corner::DLF, Corner::DBL, Corner::DRB,
+    ];
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Edge {
+    UR, UF, UL, UB, DR, DF, DL, DB, FR, FL, BL, BR,
+}
+
+impl Edge {
+    pub const ALL: [Edge; 12] = [
+        Edge::UR, Edge::UF, Edge::UL, Edge::UB, Edge::DR, Edge::DF,
+        Edge::DL, Edge::DB, Edge::FR, Edge::FL, Edge::BL, Edge::BR,
+    ];
+}
+
+/// A single piece of the cube, or the position it belongs in.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Cubie {
+    Corner(Corner),
+    Edge(Edge),
+    Centre(Face),
+}
+
+/// A 3x3x3 cube as the position and orientation of each of its pieces.
+///
+/// Each array is indexed by position and holds the piece currently sitting
+/// there. A corner's twist counts clockwise turns of its U/D sticker away
+/// from the U/D face of its position; an edge is flipped when its first
+/// sticker is not on the first face of its position. Centres only move under
+/// slice moves and their orientation is not visible, so only their position
+/// is kept.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub struct RubiksCube3x3 {
-    cube: Vec<Vec<Vec<RubiksCell>>>,
+    pub(crate) corners: [Corner; 8],
+    pub(crate) twists: [u8; 8],
+    pub(crate) edges: [Edge; 12],
+    pub(crate) flips: [u8; 12],
+    pub(crate) centres: [Face; 6],
+}
+
+/// A quarter turn of each face and slice, as it leaves a solved cube.
+/// Slices are given as well as faces, as only they move centres, and so no
+/// sequence of face turns makes one.
+fn quarter_turn(layer: Layer) -> RubiksCube3x3 {
+    use Corner::*;
+    use Edge::*;
+
+    let cube = |corners, twists, edges, flips, centres| RubiksCube3x3 { corners, twists, edges, flips, centres };
+    let face = |corners, twists, edges, flips| cube(corners, twists, edges, flips, Face::ALL);
+    let turns = |layers: &[Layer]| layers
+        .iter()
+        .fold(RubiksCube3x3::new(), |rc, &layer| rc.multiply(&quarter_turn(layer)));
+    let prime = |layer: Layer| {
+        let quarter = quarter_turn(layer);
+        quarter.multiply(&quarter).multiply(&quarter)
+    };
+
+    match layer {
+        Layer::U => face([UBR, URF, UFL, ULB, DFR, DLF, DBL, DRB],
+                         [0, 0, 0, 0, 0, 0, 0, 0],
+                         [UB, UR, UF, UL, DR, DF, DL, DB, FR, FL, BL, BR],
+                         [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+        Layer::R => face([DFR, UFL, ULB, URF, DRB, DLF, DBL, UBR],
+                         [2, 0, 0, 1, 1, 0, 0, 2],
+                         [FR, UF, UL, UB, BR, DF, DL, DB, DR, FL, BL, UR],
+                         [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+        Layer::F => face([UFL, DLF, ULB, UBR, URF, DFR, DBL, DRB],
+                         [1, 2, 0, 0, 2, 1, 0, 0],
+                         [UR, FL, UL, UB, DR, FR, DL, DB, UF, DF, BL, BR],
+                         [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0]),
+        Layer::D => face([URF, UFL, ULB, UBR, DLF, DBL, DRB, DFR],
+                         [0, 0, 0, 0, 0, 0, 0, 0],
+                         [UR, UF, UL, UB, DF, DL, DB, DR, FR, FL, BL, BR],
+                         [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+        Layer::L => face([URF, ULB, DBL, UBR, DFR, UFL, DLF, DRB],
+                         [0, 1, 2, 0, 0, 2, 1, 0],
+                         [UR, UF, BL, UB, DR, DF, FL, DB, FR, UL, DL, BR],
+                         [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+        Layer::B => face([URF, UFL, UBR, DRB, DFR, DLF, ULB, DBL],
+                         [0, 0, 1, 2, 0, 0, 2, 1],
+                         [UR, UF, UL, BR, DR, DF, DL, BL, FR, FL, UB, DB],
+                         [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1]),
+        Layer::M => cube(Corner::ALL,
+                         [0, 0, 0, 0, 0, 0, 0, 0],
+                         [UR, UB, UL, DB, DR, UF, DL, DF, FR, FL, BL, BR],
+                         [0, 1, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0],
+                         [Face::B, Face::R, Face::U, Face::F, Face::L, Face::D]),
+        Layer::E => cube(Corner::ALL,
+                         [0, 0, 0, 0, 0, 0, 0, 0],
+                         [UR, UF, UL, UB, DR, DF, DL, DB, FL, BL, BR, FR],
+                         [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1],
+                         [Face::U, Face::F, Face::L, Face::D, Face::B, Face::R]),
+        Layer::S => cube(Corner::ALL,
+                         [0, 0, 0, 0, 0, 0, 0, 0],
+                         [UL, UF, DL, UB, UR, DF, DR, DB, FR, FL, BL, BR],
+                         [1, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0, 0],
+                         [Face::L, Face::U, Face::F, Face::R, Face::D, Face::B]),
+        Layer::Uw => turns(&[Layer::U]).multiply(&prime(Layer::E)),
+        Layer::Rw => turns(&[Layer::R]).multiply(&prime(Layer::M)),
+        Layer::Fw => turns(&[Layer::F, Layer::S]),
+        Layer::Dw => turns(&[Layer::D, Layer::E]),
+        Layer::Lw => turns(&[Layer::L, Layer::M]),
+        Layer::Bw => turns(&[Layer::B]).multiply(&prime(Layer::S)),
+        Layer::X => turns(&[Layer::Rw]).multiply(&prime(Layer::L)),
+        Layer::Y => turns(&[Layer::Uw]).multiply(&prime(Layer::D)),
+        Layer::Z => turns(&[Layer::Fw]).multiply(&prime(Layer::B)),
+    }
+}
+
+/// The effect of every move on a solved cube, indexed by `Move`: each
+/// layer's quarter turn, done as many times as the move turns it.
+fn move_table() -> &'static [RubiksCube3x3; 54] {
+    static MOVES: OnceLock<[RubiksCube3x3; 54]> = OnceLock::new();
+
+    MOVES.get_or_init(|| {
+        Move::ALL.map(|m| {
+            let quarter = quarter_turn(m.layer());
+
+            (0..m.turn().quarters()).fold(RubiksCube3x3::new(), |rc, _| rc.multiply(&quarter))
+        })
+    })
+}
+
+impl Default for RubiksCube3x3 {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RubiksCube3x3 {
-    const SOLVED_CUBE: Cube = RubiksCell::collect();
-
-    pub fn new() -> Self {
-        RubiksCube3x3 {
-           cube: RubiksCube3x3::SOLVED_CUBE
-               .iter()
-               .map(|vec2d| vec2d
-                    .iter()
-                    .map(|vec| vec
-                         .iter()
-                         .copied()
-                         .collect())
-                    .collect())
-               .collect(),
+    const SOLVED_CUBE: RubiksCube3x3 = RubiksCube3x3 {
+        corners: Corner::ALL,
+        twists: [0; 8],
+        edges: Edge::ALL,
+        flips: [0; 12],
+        centres: Face::ALL,
+    };
+
+    pub const fn new() -> Self {
+        RubiksCube3x3::SOLVED_CUBE
+    }
+
+    /// The cube reached by doing `self` and then `other`, both starting
+    /// from solved.
+    pub fn multiply(&self, other: &RubiksCube3x3) -> RubiksCube3x3 {
+        let mut result = RubiksCube3x3::new();
+
+        for i in 0..8 {
+            let from = other.corners[i] as usize;
+            result.corners[i] = self.corners[from];
+            result.twists[i] = (self.twists[from] + other.twists[i]) % 3;
+        }
+
+        for i in 0..12 {
+            let from = other.edges[i] as usize;
+            result.edges[i] = self.edges[from];
+            result.flips[i] = (self.flips[from] + other.flips[i]) % 2;
         }
+
+        for i in 0..6 {
+            result.centres[i] = self.centres[other.centres[i] as usize];
+        }
+
+        result
+    }
+
+    pub fn apply(&mut self, m: Move) {
+        *self = self.multiply(&move_table()[m as usize]);
     }
 
     pub fn single_u(&mut self) {
-        let mut plane = get_x_z_plane(&mut self.cube, 0);
-        rotate90(&mut plane);
-        set_x_z_plane(&mut self.cube, &plane, 0);
+        self.apply(Move::U);
     }
 
     pub fn single_r(&mut self) {
-        let mut plane = get_y_z_plane(&mut self.cube, 2);
-        rotate90(&mut plane);
-        set_y_z_plane(&mut self.cube, &plane, 2);
+        self.apply(Move::R);
     }
 
     pub fn single_f(&mut self) {
-        let mut plane = get_x_y_plane(&mut self.cube, 0);
-        rotate90(&mut plane);
-        set_x_y_plane(&mut self.cube, &plane, 0);
+        self.apply(Move::F);
     }
 
     pub fn single_d(&mut self) {
-        let mut plane = get_x_z_plane(&mut self.cube, 2);
-        rotate90(&mut plane);
-        set_x_z_plane(&mut self.cube, &plane, 2);
+        self.apply(Move::D);
     }
 
     pub fn single_l(&mut self) {
-        let mut plane = get_y_z_plane(&mut self.cube, 0);
-        rotate90(&mut plane);
-        set_y_z_plane(&mut self.cube, &plane, 0);
+        self.apply(Move::L);
     }
 
     pub fn single_b(&mut self) {
-        let mut plane = get_x_y_plane(&mut self.cube, 2);
-        rotate90(&mut plane);
-        set_x_y_plane(&mut self.cube, &plane, 2);
+        self.apply(Move::B);
     }
 
     pub fn single_m(&mut self) {
-        let mut plane = get_y_z_plane(&mut self.cube, 1);
-        rotate90(&mut plane);
-        set_y_z_plane(&mut self.cube, &plane, 1);
+        self.apply(Move::M);
     }
 
     pub fn single_e(&mut self) {
-        let mut plane = get_x_z_plane(&mut self.cube, 1);
-        rotate90(&mut plane);
-        set_x_z_plane(&mut self.cube, &plane, 1);
+        self.apply(Move::E);
     }
 
     pub fn single_s(&mut self) {
-        let mut plane = get_x_y_plane(&mut self.cube, 1);
-        rotate90(&mut plane);
-        set_x_y_plane(&mut self.cube, &plane, 1);
+        self.apply(Move::S);
     }
 
     pub fn single_u_prime(&mut self) {
-        let mut plane = get_x_z_plane(&mut self.cube, 0);
-        rotate_minus90(&mut plane);
-        set_x_z_plane(&mut self.cube, &plane, 0);
+        self.apply(Move::UPrime);
     }
 
     pub fn single_r_prime(&mut self) {
-        let mut plane = get_y_z_plane(&mut self.cube, 2);
-        rotate_minus90(&mut plane);
-        set_y_z_plane(&mut self.cube, &plane, 2);
+        self.apply(Move::RPrime);
     }
 
     pub fn single_f_prime(&mut self) {
-        let mut plane = get_x_y_plane(&mut self.cube, 0);
-        rotate_minus90(&mut plane);
-        set_x_y_plane(&mut self.cube, &plane, 0);
+        self.apply(Move::FPrime);
     }
 
     pub fn single_d_prime(&mut self) {
-        let mut plane = get_x_z_plane(&mut self.cube, 2);
-        rotate_minus90(&mut plane);
-        set_x_z_plane(&mut self.cube, &plane, 2);
+        self.apply(Move::DPrime);
     }
 
     pub fn single_l_prime(&mut self) {
-        let mut plane = get_y_z_plane(&mut self.cube, 0);
-        rotate_minus90(&mut plane);
-        set_y_z_plane(&mut self.cube, &plane, 0);
+        self.apply(Move::LPrime);
     }
 
     pub fn single_b_prime(&mut self) {
-        let mut plane = get_x_y_plane(&mut self.cube, 2);
-        rotate_minus90(&mut plane);
-        set_x_y_plane(&mut self.cube, &plane, 2);
+        self.apply(Move::BPrime);
     }
 
     pub fn single_m_prime(&mut self) {
-        let mut plane = get_y_z_plane(&mut self.cube, 1);
-        rotate_minus90(&mut plane);
-        set_y_z_plane(&mut self.cube, &plane, 1);
+        self.apply(Move::MPrime);
     }
 
     pub fn single_e_prime(&mut self) {
-        let mut plane = get_x_z_plane(&mut self.cube, 1);
-        rotate_minus90(&mut plane);
-        set_x_z_plane(&mut self.cube, &plane, 1);
+        self.apply(Move::EPrime);
     }
 
     pub fn single_s_prime(&mut self) {
-        let mut plane = get_x_y_plane(&mut self.cube, 1);
-        rotate_minus90(&mut plane);
-        set_x_y_plane(&mut self.cube, &plane, 1);
+        self.apply(Move::SPrime);
     }
 }
 
 pub trait RubiksCube {
     fn solved(&self) -> bool;
-    fn get_cube(&self) -> &Vec<Vec<Vec<RubiksCell>>>;
 }
 
 
 impl RubiksCube for RubiksCube3x3 {
     fn solved(&self) -> bool {
-        self.cube.iter()
-            .zip(&RubiksCube3x3::SOLVED_CUBE)
-            .filter(|&(a, b)| a != b).count() == 0
-    }
-
-    fn get_cube(&self) -> &Vec<Vec<Vec<RubiksCell>>> {
-        &self.cube
+        *self == RubiksCube3x3::SOLVED_CUBE
     }
-
 }
 
 
@@ -213,7 +342,7 @@ mod tests {
     fn single_u_solves_if_repeated_four_times() {
         let mut rc = RubiksCube3x3::new();
 
-        for _ in (0..4) {
+        for _ in 0..4 {
             rc.single_u();
         }
 
@@ -237,7 +366,7 @@ mod tests {
     fn single_r_solves_if_repeated_four_times() {
         let mut rc = RubiksCube3x3::new();
 
-        for _ in (0..4) {
+        for _ in 0..4 {
             rc.single_r();
         }
 
@@ -261,7 +390,7 @@ mod tests {
     fn single_f_solves_if_repeated_four_times() {
         let mut rc = RubiksCube3x3::new();
 
-        for _ in (0..4) {
+        for _ in 0..4 {
             rc.single_f();
         }
 
@@ -285,7 +414,7 @@ mod tests {
     fn single_d_solves_if_repeated_four_times() {
         let mut rc = RubiksCube3x3::new();
 
-        for _ in (0..4) {
+        for _ in 0..4 {
             rc.single_d();
         }
 
@@ -309,7 +438,7 @@ mod tests {
     fn single_l_solves_if_repeated_four_times() {
         let mut rc = RubiksCube3x3::new();
 
-        for _ in (0..4) {
+        for _ in 0..4 {
             rc.single_l();
         }
 
@@ -333,10 +462,98 @@ mod tests {
     fn single_b_solves_if_repeated_four_times() {
         let mut rc = RubiksCube3x3::new();
 
-        for _ in (0..4) {
+        for _ in 0..4 {
             rc.single_b();
         }
 
         assert_eq!(true, rc.solved());
     }
+
+    #[test]
+    fn single_u_moves_corners_without_twisting_them() {
+        let mut rc = RubiksCube3x3::new();
+
+        rc.single_u();
+
+        assert_eq!(Corner::URF, rc.corners[Corner::UFL as usize]);
+        assert_eq!([0; 8], rc.twists);
+    }
+
+    #[test]
+    fn single_r_twists_the_corners_it_moves() {
+        let mut rc = RubiksCube3x3::new();
+
+        rc.single_r();
+
+        assert_eq!(Corner::DFR, rc.corners[Corner::URF as usize]);
+        assert_eq!([2, 0, 0, 1, 1, 0, 0, 2], rc.twists);
+    }
+
+    #[test]
+    fn single_m_moves_centres() {
+        let mut rc = RubiksCube3x3::new();
+
+        rc.single_m();
+
+        assert_eq!(Face::B, rc.centres[Face::U as usize]);
+        assert_eq!(Face::U, rc.centres[Face::F as usize]);
+        assert_eq!(false, rc.solved());
+    }
+
+    #[test]
+    fn r_then_u_is_not_solved_with_corners_twisted() {
+        let mut rc = RubiksCube3x3::new();
+
+        for _ in 0..35 {
+            rc.single_r();
+            rc.single_u();
+        }
+
+        assert_eq!(Corner::ALL, rc.corners);
+        assert_eq!(Edge::ALL, rc.edges);
+        assert_eq!(false, rc.solved());
+    }
+
+    #[test]
+    fn r_then_u_solves_after_105_repetitions() {
+        let mut rc = RubiksCube3x3::new();
+        let mut count = 0;
+
+        loop {
+            rc.single_r();
+            rc.single_u();
+            count += 1;
+
+            if rc.solved() {
+                break;
+            }
+        }
+
+        assert_eq!(105, count);
+    }
+
+    #[test]
+    fn multiply_matches_applying_moves_in_turn() {
+        let mut first = RubiksCube3x3::new();
+        first.single_r();
+        first.single_u();
+        let mut second = RubiksCube3x3::new();
+        second.single_f_prime();
+        second.single_m();
+
+        let mut both = first;
+        both.single_f_prime();
+        both.single_m();
+
+        assert_eq!(both, first.multiply(&second));
+    }
+
+    use test::Bencher;
+
+    #[bench]
+    fn bench_single_r(b: &mut Bencher) {
+        let mut rc = RubiksCube3x3::new();
+
+        b.iter(|| { rc.single_r() });
+    }
 }
@@ -0,0 +1,279 @@
+use std::sync::OnceLock;
+
+use crate::moves::*;
+use crate::optimal::rotations;
+use crate::rubiks::*;
+use crate::two_phase::{permutation_rank, permutation_unrank};
+
+/// A 2x2x2 cube as the position and twist of its eight corners, which turn
+/// just as the corners of a 3x3x3 do and are numbered the same way.
+///
+/// With no centres to say which way up it is, the cube counts as solved in
+/// any orientation. There are no slices to turn, so slice moves do nothing,
+/// and wide moves turn the whole cube.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct RubiksCube2x2 {
+    pub(crate) corners: [Corner; 8],
+    pub(crate) twists: [u8; 8],
+}
+
+/// The positions of a 2x2x2 cube turned so its DBL corner is where it
+/// belongs, the same number as there are positions once whole cube
+/// rotations are set aside: 7! arrangements of the other corners times 3^6
+/// twists.
+pub const STATES: usize = 3_674_160;
+
+/// Turns that never move DBL, which reach every position of a cube turned
+/// so that DBL is where it belongs.
+pub const FIXED_CORNER_TURNS: [Move; 9] = [
+    Move::U, Move::U2, Move::UPrime,
+    Move::R, Move::R2, Move::RPrime,
+    Move::F, Move::F2, Move::FPrime,
+];
+
+/// The rotation a wide move amounts to on a cube two layers deep.
+fn rotation(layer: Layer) -> Option<(Layer, bool)> {
+    match layer {
+        Layer::Rw => Some((Layer::X, false)),
+        Layer::Lw => Some((Layer::X, true)),
+        Layer::Uw => Some((Layer::Y, false)),
+        Layer::Dw => Some((Layer::Y, true)),
+        Layer::Fw => Some((Layer::Z, false)),
+        Layer::Bw => Some((Layer::Z, true)),
+        _ => None,
+    }
+}
+
+/// The effect of every move on a solved cube, indexed by `Move`, taken from
+/// the corners of a 3x3x3.
+fn move_table() -> &'static [RubiksCube2x2; 54] {
+    static MOVES: OnceLock<[RubiksCube2x2; 54]> = OnceLock::new();
+
+    MOVES.get_or_init(|| {
+        Move::ALL.map(|m| {
+            let m = match rotation(m.layer()) {
+                Some((layer, false)) => Move::new(layer, m.turn()),
+                Some((layer, true)) => Move::new(layer, m.turn().inverse()),
+                None => m,
+            };
+
+            let mut rc = RubiksCube3x3::new();
+            rc.apply(m);
+            RubiksCube2x2::from(&rc)
+        })
+    })
+}
+
+/// The corners of a 3x3x3, which make a 2x2x2.
+impl From<&RubiksCube3x3> for RubiksCube2x2 {
+    fn from(rc: &RubiksCube3x3) -> Self {
+        RubiksCube2x2 { corners: rc.corners, twists: rc.twists }
+    }
+}
+
+impl Default for RubiksCube2x2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RubiksCube2x2 {
+    const SOLVED_CUBE: RubiksCube2x2 = RubiksCube2x2 {
+        corners: Corner::ALL,
+        twists: [0; 8],
+    };
+
+    pub const fn new() -> Self {
+        RubiksCube2x2::SOLVED_CUBE
+    }
+
+    /// The cube reached by doing `self` and then `other`, both starting
+    /// from solved.
+    pub fn multiply(&self, other: &RubiksCube2x2) -> RubiksCube2x2 {
+        let mut result = RubiksCube2x2::new();
+
+        for i in 0..8 {
+            let from = other.corners[i] as usize;
+            result.corners[i] = self.corners[from];
+            result.twists[i] = (self.twists[from] + other.twists[i]) % 3;
+        }
+
+        result
+    }
+
+    pub fn apply(&mut self, m: Move) {
+        *self = self.multiply(&move_table()[m as usize]);
+    }
+
+    pub fn single_u(&mut self) {
+        self.apply(Move::U);
+    }
+
+    pub fn single_r(&mut self) {
+        self.apply(Move::R);
+    }
+
+    pub fn single_f(&mut self) {
+        self.apply(Move::F);
+    }
+
+    pub fn single_d(&mut self) {
+        self.apply(Move::D);
+    }
+
+    pub fn single_l(&mut self) {
+        self.apply(Move::L);
+    }
+
+    pub fn single_b(&mut self) {
+        self.apply(Move::B);
+    }
+
+    pub fn single_u_prime(&mut self) {
+        self.apply(Move::UPrime);
+    }
+
+    pub fn single_r_prime(&mut self) {
+        self.apply(Move::RPrime);
+    }
+
+    pub fn single_f_prime(&mut self) {
+        self.apply(Move::FPrime);
+    }
+
+    pub fn single_d_prime(&mut self) {
+        self.apply(Move::DPrime);
+    }
+
+    pub fn single_l_prime(&mut self) {
+        self.apply(Move::LPrime);
+    }
+
+    pub fn single_b_prime(&mut self) {
+        self.apply(Move::BPrime);
+    }
+
+    /// The cube turned as a whole so its DBL corner is where it belongs,
+    /// which looks solved exactly when `self` does.
+    pub fn upright(&self) -> RubiksCube2x2 {
+        rotations()
+            .into_iter()
+            .map(|rotation| {
+                let mut turned = *self;
+                rotation.iter().for_each(|&m| turned.apply(m));
+                turned
+            })
+            .find(|turned| turned.corners[Corner::DBL as usize] == Corner::DBL
+                && turned.twists[Corner::DBL as usize] == 0)
+            .expect("some rotation puts DBL back")
+    }
+
+    /// Numbers the positions of an upright cube from 0 to `STATES`: the
+    /// arrangement of the corners but DBL, then the twists of the first six.
+    pub(crate) fn index(&self) -> usize {
+        let others: Vec<usize> = self.corners
+            .iter()
+            .filter(|&&corner| corner != Corner::DBL)
+            .map(|&corner| corner as usize)
+            .collect();
+        let twist = self.twists[..6].iter().fold(0, |coordinate, &twist| coordinate * 3 + twist as usize);
+
+        permutation_rank(&others) * 729 + twist
+    }
+
+    /// The upright cube `index` numbers.
+    pub(crate) fn from_index(index: usize) -> RubiksCube2x2 {
+        let mut rc = RubiksCube2x2::new();
+        let others = permutation_unrank(index / 729, &[0, 1, 2, 3, 4, 5, 7]);
+        let positions = [0, 1, 2, 3, 4, 5, 7];
+
+        for (position, corner) in positions.into_iter().zip(others) {
+            rc.corners[position] = Corner::ALL[corner];
+        }
+
+        let mut twist = index % 729;
+        let mut total = 0;
+        for i in (0..6).rev() {
+            rc.twists[i] = (twist % 3) as u8;
+            total += rc.twists[i];
+            twist /= 3;
+        }
+        rc.twists[Corner::DRB as usize] = (3 - total % 3) % 3;
+
+        rc
+    }
+}
+
+impl RubiksCube for RubiksCube2x2 {
+    fn solved(&self) -> bool {
+        self.upright() == RubiksCube2x2::SOLVED_CUBE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::Algorithm;
+
+    use super::*;
+
+    fn scrambled(algorithm: &str) -> RubiksCube2x2 {
+        let mut rc = RubiksCube2x2::new();
+        algorithm.parse::<Algorithm>().unwrap().iter().for_each(|&m| rc.apply(m));
+        rc
+    }
+
+    #[test]
+    fn rubiks_cube_solved_when_created() {
+        assert!(RubiksCube2x2::new().solved());
+    }
+
+    #[test]
+    fn single_turns_solve_if_repeated_four_times() {
+        let mut rc = RubiksCube2x2::new();
+
+        for _ in 0..4 {
+            rc.single_r();
+            assert_eq!(rc == RubiksCube2x2::new(), rc.solved());
+        }
+        assert_eq!(RubiksCube2x2::new(), rc);
+
+        rc.single_f();
+        rc.single_f_prime();
+        assert_eq!(RubiksCube2x2::new(), rc);
+    }
+
+    #[test]
+    fn turns_match_corners_of_3x3() {
+        let algorithm: Algorithm = "R U F' D2 L B' M E S".parse().unwrap();
+        let mut rc = RubiksCube3x3::new();
+        algorithm.apply(&mut rc);
+
+        assert_eq!(RubiksCube2x2::from(&rc), scrambled("R U F' D2 L B'"));
+    }
+
+    #[test]
+    fn solved_in_any_orientation() {
+        assert_eq!(true, scrambled("x y2").solved());
+        assert_eq!(true, scrambled("R L'").solved());
+        assert_eq!(true, scrambled("Rw U D' Uw'").solved());
+        assert_eq!(false, scrambled("R").solved());
+    }
+
+    #[test]
+    fn wide_moves_turn_the_whole_cube() {
+        assert_eq!(scrambled("x"), scrambled("Rw"));
+        assert_eq!(scrambled("y'"), scrambled("Dw"));
+        assert_eq!(scrambled("z2"), scrambled("Bw2"));
+    }
+
+    #[test]
+    fn index_then_from_index_gives_same_cube() {
+        for algorithm in ["", "R", "R U F'", "F2 U' R2 F R' U2"] {
+            let rc = scrambled(algorithm).upright();
+
+            assert_eq!(rc, RubiksCube2x2::from_index(rc.index()));
+            assert!(rc.index() < STATES);
+        }
+        assert_eq!(STATES - 1, RubiksCube2x2::from_index(STATES - 1).index());
+    }
+}
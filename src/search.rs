@@ -0,0 +1,201 @@
+use crate::moves::*;
+
+/// The move sequences worth searching over a pool of moves: sequences that
+/// cannot be written with fewer moves from the pool by cancelling or merging
+/// turns of the same layer, and that list turns of parallel layers, which
+/// commute, in one fixed order.
+///
+/// Consecutive turns of one layer are only allowed when they spell out the
+/// shortest way to reach their combined turn with the moves in the pool, so
+/// `R R` is kept when the pool has no `R2`, but `R R'` and `R R R` never are.
+#[derive(Clone, Debug)]
+pub struct CanonicalSequences {
+    pool: Vec<Move>,
+    runs: Vec<Vec<Move>>,
+}
+
+impl CanonicalSequences {
+    pub fn new(pool: &[Move]) -> Self {
+        let mut unique: Vec<Move> = vec![];
+        for &m in pool {
+            if !unique.contains(&m) {
+                unique.push(m);
+            }
+        }
+
+        let mut layers: Vec<Layer> = unique.iter().map(|m| m.layer()).collect();
+        layers.sort();
+        layers.dedup();
+
+        let runs = layers
+            .iter()
+            .flat_map(|&layer| {
+                let moves: Vec<Move> = unique.iter()
+                    .copied()
+                    .filter(|m| m.layer() == layer)
+                    .collect();
+                shortest_runs(&moves)
+            })
+            .collect();
+
+        CanonicalSequences { pool: unique, runs }
+    }
+
+    pub fn pool(&self) -> &[Move] {
+        &self.pool
+    }
+
+    /// Whether `next` can follow `sequence`, itself canonical, and keep it
+    /// canonical.
+    pub fn allows(&self, sequence: &[Move], next: Move) -> bool {
+        let last = match sequence.last() {
+            Some(&last) => last,
+            None => return true,
+        };
+
+        if last.layer() == next.layer() {
+            let start = sequence
+                .iter()
+                .rposition(|m| m.layer() != next.layer())
+                .map_or(0, |i| i + 1);
+            let run = &sequence[start..];
+
+            return self.runs.iter().any(|canonical| canonical.len() == run.len() + 1
+                                        && canonical[..run.len()] == *run
+                                        && canonical[run.len()] == next);
+        }
+
+        last.axis() != next.axis() || last.layer() < next.layer()
+    }
+
+    /// Calls `visit` with every canonical sequence of exactly `depth` moves.
+    pub fn for_each(&self, depth: usize, mut visit: impl FnMut(&[Move])) {
+        let mut sequence = Vec::with_capacity(depth);
+        self.extend(depth, &mut sequence, &mut visit);
+    }
+
+    fn extend(&self, depth: usize, sequence: &mut Vec<Move>, visit: &mut impl FnMut(&[Move])) {
+        if sequence.len() == depth {
+            visit(sequence);
+            return;
+        }
+
+        for &m in &self.pool {
+            if self.allows(sequence, m) {
+                sequence.push(m);
+                self.extend(depth, sequence, visit);
+                sequence.pop();
+            }
+        }
+    }
+}
+
+/// For each way of turning a single layer, the shortest run of `moves`, all
+/// turns of that layer, that turns it that far. Ties go to the run that
+/// comes first in the order of `moves`.
+fn shortest_runs(moves: &[Move]) -> Vec<Vec<Move>> {
+    let mut best: [Option<Vec<Move>>; 4] = Default::default();
+    best[0] = Some(vec![]);
+    let mut frontier = vec![(0, vec![])];
+
+    while !frontier.is_empty() {
+        let mut next = vec![];
+
+        for (quarters, run) in frontier {
+            for &m in moves {
+                let reached = (quarters + m.turn().quarters()) % 4;
+
+                if best[reached].is_none() {
+                    let mut longer: Vec<Move> = run.clone();
+                    longer.push(m);
+                    best[reached] = Some(longer.clone());
+                    next.push((reached, longer));
+                }
+            }
+        }
+
+        frontier = next;
+    }
+
+    best.into_iter().skip(1).flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::rubiks::RubiksCube3x3;
+
+    fn face_turns() -> Vec<Move> {
+        Move::ALL.into_iter().filter(|m| m.layer().kind() == LayerKind::Face).collect()
+    }
+
+    fn count(sequences: &CanonicalSequences, depth: usize) -> usize {
+        let mut count = 0;
+        sequences.for_each(depth, |_| count += 1);
+        count
+    }
+
+    #[test]
+    fn counts_match_known_face_turn_sequences() {
+        let sequences = CanonicalSequences::new(&face_turns());
+
+        assert_eq!(18, count(&sequences, 1));
+        assert_eq!(243, count(&sequences, 2));
+        assert_eq!(3240, count(&sequences, 3));
+        // Only 43239 distinct positions: a few, like R2 L2 U2 D2, can still be
+        // reached by two canonical sequences.
+        assert_eq!(43254, count(&sequences, 4));
+    }
+
+    #[test]
+    fn repeats_quarter_turns_only_when_pool_has_no_half_turn() {
+        let sequences = CanonicalSequences::new(&[Move::R, Move::RPrime, Move::U, Move::UPrime]);
+
+        assert_eq!(true, sequences.allows(&[Move::R], Move::R));
+        assert_eq!(false, sequences.allows(&[Move::R], Move::RPrime));
+        assert_eq!(false, sequences.allows(&[Move::RPrime], Move::RPrime));
+        assert_eq!(false, sequences.allows(&[Move::R, Move::R], Move::R));
+        assert_eq!(10, count(&sequences, 2));
+    }
+
+    #[test]
+    fn orders_commuting_layers() {
+        let sequences = CanonicalSequences::new(&face_turns());
+
+        assert_eq!(true, sequences.allows(&[Move::U], Move::D));
+        assert_eq!(false, sequences.allows(&[Move::D], Move::U));
+        assert_eq!(false, sequences.allows(&[Move::R, Move::L], Move::R2));
+        assert_eq!(true, sequences.allows(&[Move::R, Move::L], Move::U2));
+    }
+
+    #[test]
+    fn every_sequence_has_a_canonical_equivalent_no_longer() {
+        let pool: Vec<Move> = Move::ALL
+            .into_iter()
+            .filter(|m| m.turn() != Turn::Half)
+            .filter(|m| matches!(m.layer().kind(), LayerKind::Face | LayerKind::Slice))
+            .collect();
+        let sequences = CanonicalSequences::new(&pool);
+
+        let reach = |sequence: &[Move]| {
+            let mut rc = RubiksCube3x3::new();
+            sequence.iter().for_each(|&m| rc.apply(m));
+            rc
+        };
+
+        let mut canonical = HashSet::new();
+        for depth in 0..=3 {
+            sequences.for_each(depth, |sequence| { canonical.insert(reach(sequence)); });
+        }
+
+        for &a in &pool {
+            for &b in &pool {
+                for &c in &pool {
+                    assert!(canonical.contains(&reach(&[a, b, c])), "{} {} {}", a, b, c);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,371 @@
+use std::io::{self, Read, Write};
+
+use crate::algorithm::Algorithm;
+use crate::cycles::{self, CycleType};
+use crate::equivalence::Class;
+use crate::moves::Move;
+use crate::report::Report;
+use crate::rubiks::RubiksCube3x3;
+use crate::statistics::Statistics;
+
+/// One result of a search: a class of equivalent sequences of `depth`
+/// moves, with the cycles its representative makes.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Record {
+    pub depth: usize,
+    pub sequence: Algorithm,
+    pub order: u64,
+    /// How many sequences the search found in the class.
+    pub size: usize,
+    pub corners: CycleType,
+    pub edges: CycleType,
+}
+
+impl Record {
+    pub fn new(depth: usize, class: &Class) -> Self {
+        Record::from_sequence(depth, class.representative.clone(), class.order, class.size)
+    }
+
+    fn from_sequence(depth: usize, sequence: Algorithm, order: u64, size: usize) -> Self {
+        let mut rc = RubiksCube3x3::new();
+        sequence.apply(&mut rc);
+
+        Record {
+            depth,
+            sequence,
+            order,
+            size,
+            corners: CycleType::of(&cycles::corner_cycles(&rc)),
+            edges: CycleType::of(&cycles::edge_cycles(&rc)),
+        }
+    }
+}
+
+/// Somewhere search results go, one record at a time.
+pub trait ResultSink {
+    fn write(&mut self, record: &Record) -> io::Result<()>;
+
+    /// Called once every record has been written.
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// Lines lined up in columns for reading: depth, order, class size and the
+/// sequence.
+pub struct TextSink<W: Write> {
+    out: W,
+}
+
+impl<W: Write> TextSink<W> {
+    pub fn new(out: W) -> Self {
+        TextSink { out }
+    }
+}
+
+impl<W: Write> ResultSink for TextSink<W> {
+    fn write(&mut self, record: &Record) -> io::Result<()> {
+        writeln!(self.out, "{0:<10} {1:<10} {2:<10} {3}",
+                 format!("depth: {}", record.depth),
+                 record.order,
+                 format!("x{}", record.size),
+                 record.sequence)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Comma separated values, with a header line first.
+pub struct CsvSink<W: Write> {
+    out: W,
+    started: bool,
+}
+
+impl<W: Write> CsvSink<W> {
+    pub fn new(out: W) -> Self {
+        CsvSink { out, started: false }
+    }
+}
+
+impl<W: Write> ResultSink for CsvSink<W> {
+    fn write(&mut self, record: &Record) -> io::Result<()> {
+        if !self.started {
+            writeln!(self.out, "depth,order,size,sequence,corners,edges")?;
+            self.started = true;
+        }
+
+        writeln!(self.out, "{},{},{},{},{},{}",
+                 record.depth, record.order, record.size, record.sequence, record.corners, record.edges)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// One JSON object per line. Cycle types are arrays of cycles written as in
+/// `CycleType`, e.g. `["5-", "1+"]`.
+pub struct JsonLinesSink<W: Write> {
+    out: W,
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    pub fn new(out: W) -> Self {
+        JsonLinesSink { out }
+    }
+}
+
+/// A cycle type as a JSON array of strings. Nothing in a cycle type needs
+/// escaping.
+fn json_cycle_type(cycle_type: &CycleType) -> String {
+    let cycles: Vec<String> = cycle_type.0
+        .iter()
+        .map(|&(length, twist)| format!("\"{}\"", CycleType(vec![(length, twist)])))
+        .collect();
+
+    format!("[{}]", cycles.join(","))
+}
+
+impl<W: Write> ResultSink for JsonLinesSink<W> {
+    fn write(&mut self, record: &Record) -> io::Result<()> {
+        // Moves are written with letters, digits and `'`, none of which need
+        // escaping either.
+        writeln!(self.out,
+                 "{{\"depth\":{},\"sequence\":\"{}\",\"order\":{},\"size\":{},\"corners\":{},\"edges\":{}}}",
+                 record.depth,
+                 record.sequence,
+                 record.order,
+                 record.size,
+                 json_cycle_type(&record.corners),
+                 json_cycle_type(&record.edges))
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Marks the start of the binary format, and which version it is.
+const BINARY_MAGIC: &[u8; 4] = b"RCC1";
+
+/// Little endian records of 10 bytes plus one per move, after
+/// `BINARY_MAGIC`:
+///
+/// | bytes | field                          |
+/// | ----- | ------------------------------ |
+/// | 1     | depth                          |
+/// | 4     | order                          |
+/// | 4     | class size                     |
+/// | 1     | number of moves, `n`           |
+/// | n     | each move, as its `Move` index |
+///
+/// Cycle types are left out, as they can be worked out again from the
+/// moves; `read_binary` does.
+pub struct BinarySink<W: Write> {
+    out: W,
+    started: bool,
+}
+
+impl<W: Write> BinarySink<W> {
+    pub fn new(out: W) -> Self {
+        BinarySink { out, started: false }
+    }
+}
+
+fn too_big(field: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("{} too big for binary record", field))
+}
+
+impl<W: Write> ResultSink for BinarySink<W> {
+    fn write(&mut self, record: &Record) -> io::Result<()> {
+        if !self.started {
+            self.out.write_all(BINARY_MAGIC)?;
+            self.started = true;
+        }
+
+        let depth = u8::try_from(record.depth).map_err(|_| too_big("depth"))?;
+        let order = u32::try_from(record.order).map_err(|_| too_big("order"))?;
+        let size = u32::try_from(record.size).map_err(|_| too_big("size"))?;
+        let length = u8::try_from(record.sequence.len()).map_err(|_| too_big("sequence"))?;
+
+        self.out.write_all(&[depth])?;
+        self.out.write_all(&order.to_le_bytes())?;
+        self.out.write_all(&size.to_le_bytes())?;
+        self.out.write_all(&[length])?;
+
+        let moves: Vec<u8> = record.sequence.iter().map(|&m| m as u8).collect();
+        self.out.write_all(&moves)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Reads back the records written by a `BinarySink`.
+pub fn read_binary(mut input: impl Read) -> io::Result<Vec<Record>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    let mut bytes = vec![];
+    input.read_to_end(&mut bytes)?;
+
+    if bytes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut rest = bytes.strip_prefix(BINARY_MAGIC).ok_or_else(|| invalid("not a binary record file"))?;
+    let mut records = vec![];
+
+    while !rest.is_empty() {
+        if rest.len() < 10 {
+            return Err(invalid("record cut short"));
+        }
+
+        let depth = rest[0] as usize;
+        let order = u32::from_le_bytes(rest[1..5].try_into().unwrap()) as u64;
+        let size = u32::from_le_bytes(rest[5..9].try_into().unwrap()) as usize;
+        let length = rest[9] as usize;
+
+        let moves = rest.get(10..10 + length).ok_or_else(|| invalid("record cut short"))?;
+        let sequence = moves
+            .iter()
+            .map(|&m| Move::ALL.get(m as usize).copied().ok_or_else(|| invalid("not a move")))
+            .collect::<io::Result<Algorithm>>()?;
+
+        records.push(Record::from_sequence(depth, sequence, order, size));
+        rest = &rest[10 + length..];
+    }
+
+    Ok(records)
+}
+
+/// The README tables of the longest cycles and a histogram of all of them,
+/// written once the search is over.
+pub struct MarkdownSink<W: Write> {
+    out: W,
+    report: Report,
+}
+
+impl<W: Write> MarkdownSink<W> {
+    pub fn new(out: W, report: Report) -> Self {
+        MarkdownSink { out, report }
+    }
+}
+
+impl<W: Write> ResultSink for MarkdownSink<W> {
+    fn write(&mut self, record: &Record) -> io::Result<()> {
+        self.report.add(record);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        write!(self.out, "{}", self.report)?;
+        self.out.flush()
+    }
+}
+
+/// The spread of orders at each depth, as text or CSV, written once the
+/// search is over.
+pub struct StatisticsSink<W: Write> {
+    out: W,
+    csv: bool,
+    statistics: Statistics,
+}
+
+impl<W: Write> StatisticsSink<W> {
+    pub fn text(out: W) -> Self {
+        StatisticsSink { out, csv: false, statistics: Statistics::new() }
+    }
+
+    pub fn csv(out: W) -> Self {
+        StatisticsSink { out, csv: true, statistics: Statistics::new() }
+    }
+}
+
+impl<W: Write> ResultSink for StatisticsSink<W> {
+    fn write(&mut self, record: &Record) -> io::Result<()> {
+        self.statistics.add(record.depth, record.order, record.size as u64);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let statistics = if self.csv { self.statistics.csv() } else { self.statistics.text() };
+        write!(self.out, "{}", statistics)?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(sequence: &str, size: usize) -> Record {
+        let sequence: Algorithm = sequence.parse().unwrap();
+        let mut rc = RubiksCube3x3::new();
+        sequence.apply(&mut rc);
+
+        Record::from_sequence(sequence.len(), sequence, cycles::order(&rc), size)
+    }
+
+    fn written(sink: &mut dyn ResultSink, records: &[Record]) {
+        for record in records {
+            sink.write(record).unwrap();
+        }
+        sink.finish().unwrap();
+    }
+
+    #[test]
+    fn csv_has_header_and_one_line_per_record() {
+        let mut out = vec![];
+        written(&mut CsvSink::new(&mut out), &[record("R U", 48), record("M E", 24)]);
+
+        assert_eq!("\
+depth,order,size,sequence,corners,edges
+2,105,48,R U,5- 1+,7
+2,12,24,M E,,4 4
+", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn json_lines_has_one_object_per_record() {
+        let mut out = vec![];
+        written(&mut JsonLinesSink::new(&mut out), &[record("R U'", 48)]);
+
+        assert_eq!("{\"depth\":2,\"sequence\":\"R U'\",\"order\":63,\"size\":48,\
+                    \"corners\":[\"3+\",\"3-\"],\"edges\":[\"7\"]}\n",
+                   String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn binary_reads_back_what_was_written() {
+        let records = vec![record("R U", 48), record("U R' M2 x", 7), record("F", 12)];
+        let mut out = vec![];
+        written(&mut BinarySink::new(&mut out), &records);
+
+        assert_eq!(4 + 10 * 3 + 2 + 4 + 1, out.len());
+        assert_eq!(records, read_binary(out.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn statistics_weigh_records_by_class_size() {
+        let mut out = vec![];
+        written(&mut StatisticsSink::text(&mut out), &[record("R U", 48), record("R U'", 48), record("U D", 6)]);
+
+        assert_eq!("\
+depth 2: 102 sequences, mean 79.29, median 63, max 105
+     order        count       within
+         4            6            6
+        63           48           54
+       105           48          102
+", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn binary_rejects_cut_short_records() {
+        let mut out = vec![];
+        written(&mut BinarySink::new(&mut out), &[record("R U", 48)]);
+        out.pop();
+
+        assert_eq!(io::ErrorKind::InvalidData, read_binary(out.as_slice()).unwrap_err().kind());
+    }
+}
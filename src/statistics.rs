@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+/// How the orders of a set of sequences are spread out: how many sequences
+/// have each order.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct OrderStatistics {
+    histogram: BTreeMap<u64, u64>,
+}
+
+impl OrderStatistics {
+    pub fn new() -> Self {
+        OrderStatistics::default()
+    }
+
+    /// Counts `count` more sequences of order `order`.
+    pub fn add(&mut self, order: u64, count: u64) {
+        *self.histogram.entry(order).or_default() += count;
+    }
+
+    /// How many sequences have each order, shortest first.
+    pub fn histogram(&self) -> &BTreeMap<u64, u64> {
+        &self.histogram
+    }
+
+    pub fn count(&self) -> u64 {
+        self.histogram.values().sum()
+    }
+
+    pub fn mean(&self) -> f64 {
+        let total: u64 = self.histogram.iter().map(|(order, count)| order * count).sum();
+        total as f64 / self.count() as f64
+    }
+
+    /// The order of the middle sequence, or the shorter of the two middle
+    /// ones if there is an even number of them.
+    pub fn median(&self) -> Option<u64> {
+        let middle = self.count().div_ceil(2);
+        let mut seen = 0;
+
+        self.histogram.iter().find_map(|(&order, &count)| {
+            seen += count;
+            (seen >= middle && count > 0).then_some(order)
+        })
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        self.histogram.keys().next_back().copied()
+    }
+
+    /// How many sequences get back to solved within `k` repetitions, i.e.
+    /// have an order of at most `k`.
+    pub fn within(&self, k: u64) -> u64 {
+        self.histogram.range(..=k).map(|(_, count)| count).sum()
+    }
+}
+
+/// Order statistics for each depth of a search.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Statistics {
+    depths: BTreeMap<usize, OrderStatistics>,
+}
+
+impl Statistics {
+    pub fn new() -> Self {
+        Statistics::default()
+    }
+
+    pub fn add(&mut self, depth: usize, order: u64, count: u64) {
+        self.depths.entry(depth).or_default().add(order, count);
+    }
+
+    pub fn depths(&self) -> impl Iterator<Item = (usize, &OrderStatistics)> {
+        self.depths.iter().map(|(&depth, statistics)| (depth, statistics))
+    }
+
+    /// A summary line for each depth followed by its histogram, with how
+    /// many sequences are solved within each order.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+
+        for (depth, statistics) in self.depths() {
+            text += &format!("depth {}: {} sequences, mean {:.2}, median {}, max {}\n",
+                             depth,
+                             statistics.count(),
+                             statistics.mean(),
+                             statistics.median().unwrap_or(0),
+                             statistics.max().unwrap_or(0));
+            text += &format!("{:>10} {:>12} {:>12}\n", "order", "count", "within");
+
+            for (&order, &count) in statistics.histogram() {
+                text += &format!("{:>10} {:>12} {:>12}\n", order, count, statistics.within(order));
+            }
+        }
+
+        text
+    }
+
+    /// One row for each order of each depth, with the mean and median of
+    /// the depth repeated on every row.
+    pub fn csv(&self) -> String {
+        let mut csv = String::from("depth,order,count,within,mean,median\n");
+
+        for (depth, statistics) in self.depths() {
+            for (&order, &count) in statistics.histogram() {
+                csv += &format!("{},{},{},{},{:.4},{}\n",
+                                depth,
+                                order,
+                                count,
+                                statistics.within(order),
+                                statistics.mean(),
+                                statistics.median().unwrap_or(0));
+            }
+        }
+
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> OrderStatistics {
+        let mut statistics = OrderStatistics::new();
+        statistics.add(4, 3);
+        statistics.add(105, 2);
+        statistics.add(2, 1);
+        statistics.add(4, 1);
+        statistics
+    }
+
+    #[test]
+    fn summarises_orders() {
+        let statistics = example();
+
+        assert_eq!(7, statistics.count());
+        assert_eq!((2 + 4 * 4 + 105 * 2) as f64 / 7.0, statistics.mean());
+        assert_eq!(Some(4), statistics.median());
+        assert_eq!(Some(105), statistics.max());
+    }
+
+    #[test]
+    fn within_counts_orders_up_to_k() {
+        let statistics = example();
+
+        assert_eq!(0, statistics.within(1));
+        assert_eq!(1, statistics.within(2));
+        assert_eq!(5, statistics.within(104));
+        assert_eq!(7, statistics.within(105));
+    }
+
+    #[test]
+    fn median_takes_shorter_middle_order() {
+        let mut statistics = OrderStatistics::new();
+        statistics.add(2, 1);
+        statistics.add(6, 1);
+
+        assert_eq!(Some(2), statistics.median());
+        assert_eq!(None, OrderStatistics::new().median());
+    }
+
+    #[test]
+    fn csv_has_a_row_for_each_order_of_each_depth() {
+        let mut statistics = Statistics::new();
+        statistics.add(1, 4, 18);
+        statistics.add(2, 105, 2);
+        statistics.add(2, 2, 2);
+
+        assert_eq!("\
+depth,order,count,within,mean,median
+1,4,18,18,4.0000,4
+2,2,2,2,53.5000,2
+2,105,2,4,53.5000,2
+", statistics.csv());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::moves::*;
+
+/// One of the 48 symmetries of the cube: a rotation, possibly followed by a
+/// reflection, that maps the cube onto itself.
+///
+/// It is stored as where it sends each axis: axis `i` goes to `axes[i]`,
+/// pointing the same way if `signs[i]` is positive and the other way if not.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct Symmetry {
+    axes: [Axis; 3],
+    signs: [i8; 3],
+}
+
+/// The direction, as an axis and a sign, that a layer turns clockwise about.
+/// Faces and wide layers turn about the normal pointing out of their face,
+/// slices about the normal of the face they follow, and rotations about the
+/// normal of R, U or F.
+fn normal(layer: Layer) -> (Axis, i8) {
+    match layer {
+        Layer::R | Layer::Rw | Layer::X => (Axis::X, 1),
+        Layer::L | Layer::Lw | Layer::M => (Axis::X, -1),
+        Layer::U | Layer::Uw | Layer::Y => (Axis::Y, 1),
+        Layer::D | Layer::Dw | Layer::E => (Axis::Y, -1),
+        Layer::F | Layer::Fw | Layer::S | Layer::Z => (Axis::Z, 1),
+        Layer::B | Layer::Bw => (Axis::Z, -1),
+    }
+}
+
+impl Symmetry {
+    pub const IDENTITY: Symmetry = Symmetry {
+        axes: [Axis::X, Axis::Y, Axis::Z],
+        signs: [1, 1, 1],
+    };
+
+    /// All 48 symmetries, starting with the identity.
+    pub fn all() -> Vec<Symmetry> {
+        let permutations = [
+            [Axis::X, Axis::Y, Axis::Z], [Axis::X, Axis::Z, Axis::Y],
+            [Axis::Y, Axis::X, Axis::Z], [Axis::Y, Axis::Z, Axis::X],
+            [Axis::Z, Axis::X, Axis::Y], [Axis::Z, Axis::Y, Axis::X],
+        ];
+
+        permutations
+            .into_iter()
+            .flat_map(|axes| (0..8).map(move |bits| Symmetry {
+                axes,
+                signs: [0, 1, 2].map(|i| if bits & (1 << i) == 0 { 1 } else { -1 }),
+            }))
+            .collect()
+    }
+
+    /// Whether this symmetry is a reflection, which turns clockwise turns
+    /// into anticlockwise ones.
+    pub fn is_reflection(&self) -> bool {
+        let inversions = (0..3)
+            .flat_map(|i| (i + 1..3).map(move |j| (i, j)))
+            .filter(|&(i, j)| self.axes[i] > self.axes[j])
+            .count();
+        let flips = self.signs.iter().filter(|&&sign| sign < 0).count();
+
+        (inversions + flips) % 2 == 1
+    }
+
+    /// The move that does to the mapped cube what `m` does to the original.
+    pub fn apply(&self, m: Move) -> Move {
+        let (axis, sign) = normal(m.layer());
+        let mapped = (self.axes[axis as usize], sign * self.signs[axis as usize]);
+
+        let kind = m.layer().kind();
+        let layer = Layer::ALL
+            .into_iter()
+            .find(|&layer| layer.kind() == kind && match kind {
+                LayerKind::Slice | LayerKind::Rotation => normal(layer).0 == mapped.0,
+                LayerKind::Face | LayerKind::Wide => normal(layer) == mapped,
+            })
+            .unwrap();
+
+        // Slices and rotations have only one layer per axis; when the normal
+        // lands on the opposite side of it, the turn runs the other way.
+        let reversed = self.is_reflection() != (normal(layer) != mapped);
+        let turn = if reversed { m.turn().inverse() } else { m.turn() };
+
+        Move::new(layer, turn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::algorithm::Algorithm;
+    use crate::cycles;
+    use crate::rubiks::RubiksCube3x3;
+
+    fn order_of(moves: &[Move]) -> u64 {
+        let mut rc = RubiksCube3x3::new();
+        moves.iter().for_each(|&m| rc.apply(m));
+        cycles::order(&rc)
+    }
+
+    #[test]
+    fn there_are_48_symmetries_half_of_them_reflections() {
+        let all = Symmetry::all();
+
+        assert_eq!(48, all.iter().collect::<HashSet<_>>().len());
+        assert_eq!(24, all.iter().filter(|s| s.is_reflection()).count());
+        assert_eq!(Symmetry::IDENTITY, all[0]);
+    }
+
+    #[test]
+    fn left_right_mirror_swaps_r_and_l_prime() {
+        let mirror = Symmetry { axes: [Axis::X, Axis::Y, Axis::Z], signs: [-1, 1, 1] };
+
+        assert_eq!(Move::LPrime, mirror.apply(Move::R));
+        assert_eq!(Move::UPrime, mirror.apply(Move::U));
+        assert_eq!(Move::M, mirror.apply(Move::M));
+        assert_eq!(Move::RwPrime, mirror.apply(Move::Lw));
+        assert_eq!(Move::X, mirror.apply(Move::X));
+        assert_eq!(Move::YPrime, mirror.apply(Move::Y));
+    }
+
+    #[test]
+    fn every_symmetry_maps_moves_one_to_one() {
+        for symmetry in Symmetry::all() {
+            let images: HashSet<Move> = Move::ALL.iter().map(|&m| symmetry.apply(m)).collect();
+
+            assert_eq!(Move::ALL.len(), images.len());
+        }
+    }
+
+    #[test]
+    fn every_symmetry_preserves_order() {
+        for algorithm in ["R U", "R U' F2", "R2 D' B M", "L F' E S2 U", "Rw U x M2 D"] {
+            let algorithm: Algorithm = algorithm.parse().unwrap();
+
+            for symmetry in Symmetry::all() {
+                let image: Vec<Move> = algorithm.iter().map(|&m| symmetry.apply(m)).collect();
+
+                assert_eq!(order_of(&algorithm), order_of(&image), "{} under {:?}", algorithm, symmetry);
+            }
+        }
+    }
+}
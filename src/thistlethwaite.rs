@@ -0,0 +1,448 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::algorithm::Algorithm;
+use crate::moves::Move;
+use crate::optimal::upright;
+use crate::pattern::FACE_TURNS;
+use crate::rubiks::*;
+use crate::two_phase::*;
+
+/// One of the nested groups of positions Thistlethwaite's method passes
+/// through, each reached with the moves of the one before and kept in by
+/// fewer moves of its own, down to `G4`, the solved cube alone.
+///
+/// With edges counted as flipped by F and B quarter turns, as here, the
+/// groups are generated by:
+///
+/// | group | moves                       |
+/// | ----- | --------------------------- |
+/// | `G0`  | `<U, D, R, L, F, B>`        |
+/// | `G1`  | `<U, D, R, L, F2, B2>`      |
+/// | `G2`  | `<U, D, R2, L2, F2, B2>`    |
+/// | `G3`  | `<U2, D2, R2, L2, F2, B2>`  |
+/// | `G4`  | nothing                     |
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash)]
+pub enum Subgroup {
+    G0, G1, G2, G3, G4,
+}
+
+const G1_TURNS: [Move; 14] = [
+    Move::U, Move::U2, Move::UPrime,
+    Move::R, Move::R2, Move::RPrime,
+    Move::F2,
+    Move::D, Move::D2, Move::DPrime,
+    Move::L, Move::L2, Move::LPrime,
+    Move::B2,
+];
+
+const G3_TURNS: [Move; 6] = [Move::U2, Move::R2, Move::F2, Move::D2, Move::L2, Move::B2];
+
+/// The edges of the M slice, between R and L, and the S slice, between F
+/// and B.
+const M_SLICE: [Edge; 4] = [Edge::UF, Edge::UB, Edge::DF, Edge::DB];
+const S_SLICE: [Edge; 4] = [Edge::UR, Edge::UL, Edge::DR, Edge::DL];
+
+impl Subgroup {
+    pub const ALL: [Subgroup; 5] = [Subgroup::G0, Subgroup::G1, Subgroup::G2, Subgroup::G3, Subgroup::G4];
+
+    /// The face turns that keep a cube in the group.
+    pub fn generators(self) -> &'static [Move] {
+        match self {
+            Subgroup::G0 => FACE_TURNS,
+            Subgroup::G1 => &G1_TURNS,
+            Subgroup::G2 => &PHASE_TWO_TURNS,
+            Subgroup::G3 => &G3_TURNS,
+            Subgroup::G4 => &[],
+        }
+    }
+
+    /// Whether `rc` can be reached from solved with the group's moves alone.
+    /// Cubes whose centres slice moves have left out of place are in none of
+    /// them.
+    pub fn contains(self, rc: &RubiksCube3x3) -> bool {
+        if rc.centres != Face::ALL {
+            return false;
+        }
+
+        match self {
+            Subgroup::G0 => true,
+            Subgroup::G1 => flip(rc) == 0,
+            Subgroup::G2 => Subgroup::G1.contains(rc) && twist(rc) == 0 && slice(rc) == slice(&RubiksCube3x3::new()),
+            Subgroup::G3 => {
+                Subgroup::G2.contains(rc)
+                    && half_turn_corners().index.contains_key(&rc.corners)
+                    && M_SLICE.iter().all(|edge| M_SLICE.contains(&rc.edges[*edge as usize]))
+            }
+            Subgroup::G4 => rc.solved(),
+        }
+    }
+
+    /// The smallest group `rc` is in, or `None` if its centres are out of
+    /// place.
+    pub fn of(rc: &RubiksCube3x3) -> Option<Subgroup> {
+        Subgroup::ALL.into_iter().rev().find(|group| group.contains(rc))
+    }
+}
+
+impl fmt::Display for Subgroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// The 96 ways half turns can arrange the corners, each with a number.
+struct HalfTurnCorners {
+    arrangements: Vec<[Corner; 8]>,
+    index: HashMap<[Corner; 8], usize>,
+}
+
+fn half_turn_corners() -> &'static HalfTurnCorners {
+    static CORNERS: OnceLock<HalfTurnCorners> = OnceLock::new();
+
+    CORNERS.get_or_init(|| {
+        let mut arrangements = vec![Corner::ALL];
+        let mut index = HashMap::from([(Corner::ALL, 0)]);
+        let mut next = 0;
+
+        while let Some(&corners) = arrangements.get(next) {
+            next += 1;
+
+            for m in G3_TURNS {
+                let mut rc = RubiksCube3x3 { corners, ..RubiksCube3x3::new() };
+                rc.apply(m);
+
+                if let Entry::Vacant(entry) = index.entry(rc.corners) {
+                    entry.insert(arrangements.len());
+                    arrangements.push(rc.corners);
+                }
+            }
+        }
+
+        HalfTurnCorners { arrangements, index }
+    })
+}
+
+fn half_turn_corner_index(rc: &RubiksCube3x3) -> usize {
+    half_turn_corners().index[&rc.corners]
+}
+
+fn set_half_turn_corner_index(rc: &mut RubiksCube3x3, index: usize) {
+    rc.corners = half_turn_corners().arrangements[index];
+}
+
+/// The order of `edges` among their own positions, once they are all there.
+fn edge_order(rc: &RubiksCube3x3, edges: [Edge; 4]) -> usize {
+    permutation_rank(&edges.map(|edge| rc.edges[edge as usize] as usize))
+}
+
+fn set_edge_order(rc: &mut RubiksCube3x3, coordinate: usize, edges: [Edge; 4]) {
+    let order = permutation_unrank(coordinate, &edges.map(|edge| edge as usize));
+    for (edge, piece) in edges.into_iter().zip(order) {
+        rc.edges[edge as usize] = Edge::ALL[piece];
+    }
+}
+
+/// A coordinate: how many values it has, how to read it off a cube and how
+/// to set it on one.
+type Coordinate = (usize, fn(&RubiksCube3x3) -> usize, fn(&mut RubiksCube3x3, usize));
+
+/// How far every position is from the next group down, in the moves of the
+/// group it is in. A position is followed by a few coordinates, which
+/// together say which coset of the next group it is in.
+struct PhaseTable {
+    moves: &'static [Move],
+    coordinates: Vec<fn(&RubiksCube3x3) -> usize>,
+    sizes: Vec<usize>,
+    distances: Vec<u8>,
+}
+
+impl PhaseTable {
+    /// A breadth first search out from every position in `goals`, each
+    /// given as its coordinates.
+    fn new(moves: &'static [Move],
+           coordinates: Vec<Coordinate>,
+           goals: Vec<Vec<usize>>) -> Self {
+        let tables: Vec<MoveTable> = coordinates
+            .iter()
+            .map(|&(size, get, set)| MoveTable::new(size, moves, get, set))
+            .collect();
+        let mut table = PhaseTable {
+            moves,
+            coordinates: coordinates.iter().map(|&(_, get, _)| get).collect(),
+            sizes: coordinates.iter().map(|&(size, _, _)| size).collect(),
+            distances: vec![],
+        };
+        table.distances = vec![u8::MAX; table.sizes.iter().product()];
+
+        let mut frontier: Vec<usize> = goals.iter().map(|goal| table.index(goal)).collect();
+        frontier.iter().for_each(|&index| table.distances[index] = 0);
+        let mut depth = 0;
+        let mut values = vec![0; tables.len()];
+
+        while !frontier.is_empty() {
+            let mut next = vec![];
+
+            for index in frontier {
+                let mut rest = index;
+                for i in (0..tables.len()).rev() {
+                    values[i] = rest % table.sizes[i];
+                    rest /= table.sizes[i];
+                }
+
+                for m in 0..moves.len() {
+                    let reached = tables
+                        .iter()
+                        .zip(&values)
+                        .zip(&table.sizes)
+                        .fold(0, |index, ((table, &value), &size)| index * size + table.apply(value, m));
+
+                    if table.distances[reached] == u8::MAX {
+                        table.distances[reached] = depth + 1;
+                        next.push(reached);
+                    }
+                }
+            }
+
+            frontier = next;
+            depth += 1;
+        }
+
+        table
+    }
+
+    fn index(&self, values: &[usize]) -> usize {
+        values.iter().zip(&self.sizes).fold(0, |index, (&value, &size)| index * size + value)
+    }
+
+    fn distance(&self, rc: &RubiksCube3x3) -> u8 {
+        let values: Vec<usize> = self.coordinates.iter().map(|get| get(rc)).collect();
+        self.distances[self.index(&values)]
+    }
+
+    /// The fewest moves of this phase that take `rc` into the next group,
+    /// found by always taking a move that gets a step closer.
+    fn solve(&self, rc: &mut RubiksCube3x3) -> Algorithm {
+        let mut moves = vec![];
+        let mut distance = self.distance(rc);
+
+        while distance > 0 {
+            let (m, turned) = self.moves
+                .iter()
+                .map(|&m| {
+                    let mut turned = *rc;
+                    turned.apply(m);
+                    (m, turned)
+                })
+                .find(|(_, turned)| self.distance(turned) < distance)
+                .expect("every position has a move that gets closer");
+
+            moves.push(m);
+            *rc = turned;
+            distance -= 1;
+        }
+
+        Algorithm(moves)
+    }
+}
+
+/// The moves of one phase and where they leave the cube.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Phase {
+    pub moves: Algorithm,
+    pub cube: RubiksCube3x3,
+    /// The group `cube` is in, which the next phase keeps it in.
+    pub subgroup: Subgroup,
+}
+
+/// A solution in Thistlethwaite's four phases, after a rotation if slice
+/// moves left the centres out of place.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Solution {
+    pub rotation: Algorithm,
+    pub phases: Vec<Phase>,
+}
+
+impl Solution {
+    /// Every move of the solution, one phase after another.
+    pub fn algorithm(&self) -> Algorithm {
+        self.rotation
+            .iter()
+            .chain(self.phases.iter().flat_map(|phase| phase.moves.iter()))
+            .copied()
+            .collect()
+    }
+}
+
+/// Solves a cube by taking it down Thistlethwaite's chain of groups, from
+/// `G0` to `G4`, each phase using only the moves of the group the cube is in
+/// and a table of how far each coset of the next group is. Each phase is as
+/// short as it can be, though the whole is not; solutions are at most 52
+/// moves and usually around 30.
+pub struct ThistlethwaiteSolver {
+    phases: [PhaseTable; 4],
+}
+
+impl Default for ThistlethwaiteSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn m_slice(rc: &RubiksCube3x3) -> usize {
+    edge_positions(rc, M_SLICE)
+}
+
+fn set_m_slice(rc: &mut RubiksCube3x3, coordinate: usize) {
+    set_edge_positions(rc, coordinate, M_SLICE)
+}
+
+fn m_order(rc: &RubiksCube3x3) -> usize {
+    edge_order(rc, M_SLICE)
+}
+
+fn set_m_order(rc: &mut RubiksCube3x3, coordinate: usize) {
+    set_edge_order(rc, coordinate, M_SLICE)
+}
+
+fn s_order(rc: &RubiksCube3x3) -> usize {
+    edge_order(rc, S_SLICE)
+}
+
+fn set_s_order(rc: &mut RubiksCube3x3, coordinate: usize) {
+    set_edge_order(rc, coordinate, S_SLICE)
+}
+
+fn e_order(rc: &RubiksCube3x3) -> usize {
+    edge_order(rc, E_SLICE)
+}
+
+fn set_e_order(rc: &mut RubiksCube3x3, coordinate: usize) {
+    set_edge_order(rc, coordinate, E_SLICE)
+}
+
+impl ThistlethwaiteSolver {
+    /// Works out the table for each phase, which takes a second or two in a
+    /// release build.
+    pub fn new() -> Self {
+        let solved = RubiksCube3x3::new();
+
+        // G0 to G1: flip the edges the right way.
+        let first = PhaseTable::new(FACE_TURNS, vec![(2048, flip, set_flip)], vec![vec![0]]);
+
+        // G1 to G2: untwist the corners and put the E slice edges in it.
+        let second = PhaseTable::new(&G1_TURNS,
+                                     vec![(2187, twist, set_twist), (495, slice, set_slice)],
+                                     vec![vec![0, slice(&solved)]]);
+
+        // G2 to G3: put the corners in an arrangement half turns reach, and
+        // the M slice edges in it, which leaves the S slice edges in theirs.
+        let goals = half_turn_corners()
+            .arrangements
+            .iter()
+            .map(|&corners| vec![corner_permutation(&RubiksCube3x3 { corners, ..solved }), m_slice(&solved)])
+            .collect();
+        let third = PhaseTable::new(&PHASE_TWO_TURNS,
+                                    vec![(40320, corner_permutation, set_corner_permutation),
+                                         (495, m_slice, set_m_slice)],
+                                    goals);
+
+        // G3 to G4: solve with half turns.
+        let fourth = PhaseTable::new(&G3_TURNS,
+                                     vec![(96, half_turn_corner_index, set_half_turn_corner_index),
+                                          (24, m_order, set_m_order),
+                                          (24, s_order, set_s_order),
+                                          (24, e_order, set_e_order)],
+                                     vec![vec![0, 0, 0, 0]]);
+
+        ThistlethwaiteSolver { phases: [first, second, third, fourth] }
+    }
+
+    /// Takes `rc` down to solved a group at a time, or `None` if it cannot
+    /// be, which only happens to cubes that were put together wrong.
+    pub fn solve(&self, rc: &RubiksCube3x3) -> Option<Solution> {
+        let (rotation, mut rc) = upright(rc);
+        let mut phases = vec![];
+
+        for (table, subgroup) in self.phases.iter().zip(&Subgroup::ALL[1..]) {
+            if table.distance(&rc) == u8::MAX {
+                return None;
+            }
+
+            let moves = table.solve(&mut rc);
+            debug_assert!(subgroup.contains(&rc));
+            phases.push(Phase { moves, cube: rc, subgroup: *subgroup });
+        }
+
+        Some(Solution { rotation, phases })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solver() -> &'static ThistlethwaiteSolver {
+        static SOLVER: OnceLock<ThistlethwaiteSolver> = OnceLock::new();
+        SOLVER.get_or_init(ThistlethwaiteSolver::new)
+    }
+
+    fn scrambled(algorithm: &str) -> RubiksCube3x3 {
+        let mut rc = RubiksCube3x3::new();
+        algorithm.parse::<Algorithm>().unwrap().apply(&mut rc);
+        rc
+    }
+
+    #[test]
+    fn half_turns_arrange_corners_96_ways() {
+        assert_eq!(96, half_turn_corners().arrangements.len());
+    }
+
+    #[test]
+    fn subgroup_of_is_smallest_group_generated_by_the_moves() {
+        assert_eq!(Some(Subgroup::G4), Subgroup::of(&scrambled("")));
+        assert_eq!(Some(Subgroup::G3), Subgroup::of(&scrambled("R2 U2 F2 D2")));
+        assert_eq!(Some(Subgroup::G2), Subgroup::of(&scrambled("R2 U F2 D'")));
+        assert_eq!(Some(Subgroup::G1), Subgroup::of(&scrambled("R U L' F2")));
+        assert_eq!(Some(Subgroup::G0), Subgroup::of(&scrambled("F")));
+        assert_eq!(None, Subgroup::of(&scrambled("M")));
+    }
+
+    #[test]
+    fn slice_moves_that_put_centres_back_stay_in_their_groups() {
+        // Each M2 is R2 L2 with the cube turned over, which this puts back.
+        assert_eq!(Some(Subgroup::G3), Subgroup::of(&scrambled("M2 U2 M2 U2")));
+        assert_eq!(Some(Subgroup::G2), Subgroup::of(&scrambled("M2 U M2")));
+        assert_eq!(Some(Subgroup::G3), Subgroup::of(&scrambled("M2 U M2 U2 M2 U M2")));
+    }
+
+    #[test]
+    fn group_generators_stay_in_the_group() {
+        for group in Subgroup::ALL {
+            for &m in group.generators() {
+                assert!(group.contains(&scrambled(&m.to_string())), "{} leaves {}", m, group);
+            }
+        }
+    }
+
+    #[test]
+    fn each_phase_reaches_next_group() {
+        let superflip = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+
+        for algorithm in ["", "R U R' U'", superflip, "F R2 D' B L U2 R' F' D B2 L' U M"] {
+            let mut rc = scrambled(algorithm);
+            let solution = solver().solve(&rc).unwrap();
+
+            for (phase, group) in solution.phases.iter().zip(&Subgroup::ALL[1..]) {
+                assert_eq!(*group, phase.subgroup);
+                assert!(group.contains(&phase.cube));
+                assert!(phase.moves.iter().all(|m| Subgroup::ALL[*group as usize - 1].generators().contains(m)));
+            }
+
+            solution.algorithm().apply(&mut rc);
+            assert!(rc.solved(), "{} does not solve {}", solution.algorithm(), algorithm);
+        }
+    }
+}
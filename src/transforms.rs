@@ -1,18 +1,18 @@
 type Line<T> = Vec<T>;
 type Square<T> = Vec<Line<T>>;
-type Cube<T> = Vec<Square<T>>;
 
-pub fn reverse_rows<T>(vector: &mut Vec<Vec<T>>) {
+pub fn reverse_rows<T>(vector: &mut [Vec<T>]) {
     for row in vector.iter_mut() {
         row.reverse();
     }
 }
 
-pub fn reverse_columns<T>(vector: &mut Vec<Vec<T>>) {
+pub fn reverse_columns<T>(vector: &mut [Vec<T>]) {
     vector.reverse();
 }
 
-pub fn transpose<T: Copy>(vector: &mut Vec<Vec<T>>) {
+#[allow(clippy::needless_range_loop)]
+pub fn transpose<T: Copy>(vector: &mut [Vec<T>]) {
     for n in 0..vector.len() {
         for m in n+1..vector.len() {
             let temp = vector[n][m];
@@ -22,22 +22,22 @@ pub fn transpose<T: Copy>(vector: &mut Vec<Vec<T>>) {
     }
 }
 
-pub fn rotate90<T: Copy>(vector: &mut Vec<Vec<T>>) {
+pub fn rotate90<T: Copy>(vector: &mut [Vec<T>]) {
     transpose(vector);
     reverse_rows(vector);
 }
 
-pub fn rotate_minus90<T: Copy>(vector: &mut Vec<Vec<T>>) {
+pub fn rotate_minus90<T: Copy>(vector: &mut [Vec<T>]) {
     transpose(vector);
     reverse_columns(vector);
 }
 
-// pub fn rotate_180<T: Copy>(vector: &mut Vec<Vec<T>>) {
-//     reverse_columns(vector);
-//     reverse_rows(vector);
-// }
+pub fn rotate_180<T>(vector: &mut [Vec<T>]) {
+    reverse_columns(vector);
+    reverse_rows(vector);
+}
 
-pub fn get_x_y_plane<T: Copy>(cube: &mut Vec<Vec<Vec<T>>>, idx: usize) -> Vec<Vec<T>> {
+pub fn get_x_y_plane<T: Copy>(cube: &[Square<T>], idx: usize) -> Vec<Vec<T>> {
     if cube.len() < idx {
         panic!("Index {} is out of bounds for cube length: {}", idx, cube.len());
     }
@@ -45,13 +45,12 @@ pub fn get_x_y_plane<T: Copy>(cube: &mut Vec<Vec<Vec<T>>>, idx: usize) -> Vec<Ve
         .iter()
         .skip(idx)
         .take(1)
-        .map(|x| x.clone())
-        .flatten()
+        .flat_map(|x| x.clone())
         .collect()
 
 }
 
-pub fn set_x_y_plane<T: Copy>(cube: &mut Cube<T>, square: &Square<T>, idx: usize) {
+pub fn set_x_y_plane<T: Copy>(cube: &mut [Square<T>], square: &[Line<T>], idx: usize) {
     cube
         .iter_mut()
         .skip(idx)
@@ -62,7 +61,7 @@ pub fn set_x_y_plane<T: Copy>(cube: &mut Cube<T>, square: &Square<T>, idx: usize
         .for_each(|(c, s)| *c = *s);
 }
 
-pub fn get_y_z_plane<T: Copy>(cube: &mut Vec<Vec<Vec<T>>>, idx: usize) -> Vec<Vec<T>> {
+pub fn get_y_z_plane<T: Copy>(cube: &[Square<T>], idx: usize) -> Vec<Vec<T>> {
     cube
         .iter()
         .map(|square| square
@@ -75,10 +74,10 @@ pub fn get_y_z_plane<T: Copy>(cube: &mut Vec<Vec<Vec<T>>>, idx: usize) -> Vec<Ve
         .collect()
 }
 
-pub fn set_y_z_plane<T: Copy>(cube: &mut Cube<T>, square: &Square<T>, idx: usize) {
+pub fn set_y_z_plane<T: Copy>(cube: &mut [Square<T>], square: &[Line<T>], idx: usize) {
     cube
         .iter_mut()
-        .map::<Line<&mut T>, _>(|square| {
+        .flat_map::<Line<&mut T>, _>(|square| {
             let size = square.len();
             square
              .iter_mut()
@@ -87,12 +86,11 @@ pub fn set_y_z_plane<T: Copy>(cube: &mut Cube<T>, square: &Square<T>, idx: usize
              .step_by(size)
              .collect()
         })
-        .flatten()
         .zip(square.iter().flatten())
         .for_each(|(c, s)| *c = *s);
 }
 
-pub fn get_x_z_plane<T: Copy>(cube: &mut Vec<Vec<Vec<T>>>, idx: usize) -> Vec<Vec<T>> {
+pub fn get_x_z_plane<T: Copy>(cube: &[Square<T>], idx: usize) -> Vec<Vec<T>> {
     cube
         .iter()
         .map(|square| square
@@ -105,25 +103,26 @@ pub fn get_x_z_plane<T: Copy>(cube: &mut Vec<Vec<Vec<T>>>, idx: usize) -> Vec<Ve
         .collect()
 }
 
-pub fn set_x_z_plane<T: Copy>(cube: &mut Cube<T>, square: &Square<T>, idx: usize) {
+pub fn set_x_z_plane<T: Copy>(cube: &mut [Square<T>], square: &[Line<T>], idx: usize) {
     let size = cube.len();
     cube
         .iter_mut()
-        .map::<Line<&mut T>, _>(|square| square
+        .flat_map::<Line<&mut T>, _>(|square| square
              .iter_mut()
              .flatten()
              .skip(idx*size)
              .take(size)
              .collect())
-        .flatten()
         .zip(square.iter().flatten())
         .for_each(|(c, s)| *c = *s);
 }
 
 #[cfg(test)]
+#[allow(clippy::unnecessary_mut_passed)]
 mod tests {
     use super::*;
 
+    #[allow(non_snake_case, clippy::ptr_arg, clippy::needless_return)]
     fn vec_compare<T: std::cmp::Eq>(A: &Vec<T>, B: &Vec<T>) -> bool {
         assert_eq!(A.len(), B.len(), "vectors not the same length");
 
@@ -136,6 +135,7 @@ mod tests {
         return true;
     }
 
+    #[allow(dead_code, non_snake_case, clippy::ptr_arg, clippy::needless_return, clippy::bool_comparison)]
     fn vec2d_compare<T: std::cmp::Eq>(A: &Vec<Vec<T>>, B: &Vec<Vec<T>>) -> bool {
         assert_eq!(A.len(), B.len(), "vectors not the same length");
 
@@ -0,0 +1,644 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::algorithm::Algorithm;
+use crate::moves::*;
+use crate::optimal::upright;
+use crate::pattern::FACE_TURNS;
+use crate::rubiks::*;
+use crate::search::CanonicalSequences;
+
+/// The moves that keep a cube in the subgroup phase one reduces to: any
+/// turn of U and D, and half turns of the other faces.
+pub const PHASE_TWO_TURNS: [Move; 10] = [
+    Move::U, Move::U2, Move::UPrime, Move::D, Move::D2, Move::DPrime,
+    Move::R2, Move::F2, Move::L2, Move::B2,
+];
+
+/// How long a solution is allowed to get before the solver keeps looking
+/// for a shorter one.
+pub const DEFAULT_MAX_LENGTH: usize = 30;
+
+/// The longest phase two is searched for, as no position of the subgroup
+/// needs more.
+const PHASE_TWO_MAX: usize = 18;
+
+const SLICE_POSITIONS: usize = 495;
+
+/// Marks the start of saved two-phase tables, and which version they are.
+const MAGIC: &[u8; 4] = b"RCK1";
+
+fn factorial(n: usize) -> usize {
+    (1..=n).product()
+}
+
+pub(crate) fn choose(n: usize, k: usize) -> usize {
+    if k > n { 0 } else { factorial(n) / factorial(k) / factorial(n - k) }
+}
+
+/// The position of `values` among all orderings of the same values, counting
+/// from 0 for the one sorted smallest first.
+pub(crate) fn permutation_rank(values: &[usize]) -> usize {
+    (0..values.len()).fold(0, |rank, i| {
+        let smaller_later = values[i + 1..].iter().filter(|&&later| later < values[i]).count();
+        rank * (values.len() - i) + smaller_later
+    })
+}
+
+/// The ordering of `sorted` with rank `rank`.
+pub(crate) fn permutation_unrank(mut rank: usize, sorted: &[usize]) -> Vec<usize> {
+    let mut smaller_later = vec![0; sorted.len()];
+    for i in (0..sorted.len()).rev() {
+        smaller_later[i] = rank % (sorted.len() - i);
+        rank /= sorted.len() - i;
+    }
+
+    let mut left = sorted.to_vec();
+    smaller_later.into_iter().map(|skip| left.remove(skip)).collect()
+}
+
+/// How the corners are twisted, the last twist following from the others.
+pub(crate) fn twist(rc: &RubiksCube3x3) -> usize {
+    rc.twists[..7].iter().fold(0, |coordinate, &twist| coordinate * 3 + twist as usize)
+}
+
+pub(crate) fn set_twist(rc: &mut RubiksCube3x3, mut coordinate: usize) {
+    let mut total = 0;
+    for i in (0..7).rev() {
+        rc.twists[i] = (coordinate % 3) as u8;
+        total += rc.twists[i];
+        coordinate /= 3;
+    }
+    rc.twists[7] = (3 - total % 3) % 3;
+}
+
+/// How the edges are flipped, the last flip following from the others.
+pub(crate) fn flip(rc: &RubiksCube3x3) -> usize {
+    rc.flips[..11].iter().fold(0, |coordinate, &flip| coordinate * 2 + flip as usize)
+}
+
+pub(crate) fn set_flip(rc: &mut RubiksCube3x3, mut coordinate: usize) {
+    let mut total = 0;
+    for i in (0..11).rev() {
+        rc.flips[i] = (coordinate % 2) as u8;
+        total += rc.flips[i];
+        coordinate /= 2;
+    }
+    rc.flips[11] = total % 2;
+}
+
+/// The edges of the E slice, between U and D.
+pub(crate) const E_SLICE: [Edge; 4] = [Edge::FR, Edge::FL, Edge::BL, Edge::BR];
+
+/// Which four positions `edges` are in, whatever their order.
+pub(crate) fn edge_positions(rc: &RubiksCube3x3, edges: [Edge; 4]) -> usize {
+    rc.edges
+        .iter()
+        .enumerate()
+        .filter(|&(_, edge)| edges.contains(edge))
+        .enumerate()
+        .map(|(k, (position, _))| choose(position, k + 1))
+        .sum()
+}
+
+/// Puts `edges` in the positions `coordinate` stands for, and the other
+/// edges in the rest, each in order.
+pub(crate) fn set_edge_positions(rc: &mut RubiksCube3x3, mut coordinate: usize, edges: [Edge; 4]) {
+    let mut chosen = [false; 12];
+    for k in (1..=4).rev() {
+        let position = (0..12).rev().find(|&position| choose(position, k) <= coordinate).unwrap();
+        chosen[position] = true;
+        coordinate -= choose(position, k);
+    }
+
+    let mut these = edges.into_iter();
+    let mut others = Edge::ALL.into_iter().filter(|edge| !edges.contains(edge));
+    for (edge, chosen) in rc.edges.iter_mut().zip(chosen) {
+        *edge = if chosen { these.next() } else { others.next() }.unwrap();
+    }
+}
+
+/// Which four positions the edges of the E slice are in.
+pub(crate) fn slice(rc: &RubiksCube3x3) -> usize {
+    edge_positions(rc, E_SLICE)
+}
+
+pub(crate) fn set_slice(rc: &mut RubiksCube3x3, coordinate: usize) {
+    set_edge_positions(rc, coordinate, E_SLICE)
+}
+
+pub(crate) fn corner_permutation(rc: &RubiksCube3x3) -> usize {
+    permutation_rank(&rc.corners.map(|corner| corner as usize))
+}
+
+pub(crate) fn set_corner_permutation(rc: &mut RubiksCube3x3, coordinate: usize) {
+    let corners = permutation_unrank(coordinate, &[0, 1, 2, 3, 4, 5, 6, 7]);
+    for (position, corner) in corners.into_iter().enumerate() {
+        rc.corners[position] = Corner::ALL[corner];
+    }
+}
+
+/// The order of the U and D layer edges, once phase one has put them all
+/// in those layers.
+fn layer_edge_permutation(rc: &RubiksCube3x3) -> usize {
+    let edges: Vec<usize> = rc.edges[..8].iter().map(|&edge| edge as usize).collect();
+    permutation_rank(&edges)
+}
+
+fn set_layer_edge_permutation(rc: &mut RubiksCube3x3, coordinate: usize) {
+    let edges = permutation_unrank(coordinate, &[0, 1, 2, 3, 4, 5, 6, 7]);
+    for (position, edge) in edges.into_iter().enumerate() {
+        rc.edges[position] = Edge::ALL[edge];
+    }
+}
+
+/// The order of the E slice edges, once phase one has put them in the
+/// slice.
+fn slice_permutation(rc: &RubiksCube3x3) -> usize {
+    let edges: Vec<usize> = rc.edges[8..].iter().map(|&edge| edge as usize).collect();
+    permutation_rank(&edges)
+}
+
+fn set_slice_permutation(rc: &mut RubiksCube3x3, coordinate: usize) {
+    let edges = permutation_unrank(coordinate, &[8, 9, 10, 11]);
+    for (position, edge) in edges.into_iter().enumerate() {
+        rc.edges[8 + position] = Edge::ALL[edge];
+    }
+}
+
+/// Where each value of a coordinate goes under each of `moves`, so the
+/// search can follow a coordinate without turning whole cubes.
+pub(crate) struct MoveTable {
+    moves: usize,
+    to: Vec<u16>,
+}
+
+impl MoveTable {
+    pub(crate) fn new(size: usize,
+                      moves: &[Move],
+                      get: impl Fn(&RubiksCube3x3) -> usize,
+                      set: impl Fn(&mut RubiksCube3x3, usize)) -> Self {
+        let mut to = Vec::with_capacity(size * moves.len());
+
+        for coordinate in 0..size {
+            let mut rc = RubiksCube3x3::new();
+            set(&mut rc, coordinate);
+
+            for &m in moves {
+                let mut turned = rc;
+                turned.apply(m);
+                to.push(get(&turned) as u16);
+            }
+        }
+
+        MoveTable { moves: moves.len(), to }
+    }
+
+    /// A table of the right size for `size` values and `moves` moves, to be
+    /// filled in from a saved one.
+    fn empty(size: usize, moves: usize) -> Self {
+        MoveTable { moves, to: vec![0; size * moves] }
+    }
+
+    pub(crate) fn apply(&self, coordinate: usize, m: usize) -> usize {
+        self.to[coordinate * self.moves + m] as usize
+    }
+
+    /// How many values the coordinate has.
+    pub(crate) fn size(&self) -> usize {
+        self.to.len() / self.moves
+    }
+}
+
+/// The fewest moves that solve two coordinates together, for every pair of
+/// their values, found by a breadth first search from solved.
+struct PruningTable {
+    second_size: usize,
+    distances: Vec<u8>,
+}
+
+impl PruningTable {
+    fn new(first: &MoveTable, first_solved: usize, second: &MoveTable, second_solved: usize) -> Self {
+        let PruningTable { second_size, mut distances } = PruningTable::empty(first, second);
+
+        distances[first_solved * second_size + second_solved] = 0;
+        let mut frontier = vec![first_solved * second_size + second_solved];
+        let mut depth = 0;
+
+        while !frontier.is_empty() {
+            let mut next = vec![];
+
+            for index in frontier {
+                let (a, b) = (index / second_size, index % second_size);
+
+                for m in 0..first.moves {
+                    let reached = first.apply(a, m) * second_size + second.apply(b, m);
+                    if distances[reached] == u8::MAX {
+                        distances[reached] = depth + 1;
+                        next.push(reached);
+                    }
+                }
+            }
+
+            frontier = next;
+            depth += 1;
+        }
+
+        PruningTable { second_size, distances }
+    }
+
+    fn empty(first: &MoveTable, second: &MoveTable) -> Self {
+        PruningTable { second_size: second.size(), distances: vec![u8::MAX; first.size() * second.size()] }
+    }
+
+    fn distance(&self, first: usize, second: usize) -> usize {
+        self.distances[first * self.second_size + second] as usize
+    }
+}
+
+/// A phase one position: corner twist, edge flip and where the slice edges
+/// are.
+#[derive(Clone, Copy)]
+struct PhaseOne {
+    twist: usize,
+    flip: usize,
+    slice: usize,
+}
+
+/// A phase two position: the order of the corners, of the U and D layer
+/// edges, and of the slice edges.
+#[derive(Clone, Copy)]
+struct PhaseTwo {
+    corners: usize,
+    edges: usize,
+    slice: usize,
+}
+
+/// Solves any cube quickly in two steps, after Kociemba: first into the
+/// subgroup `<U, D, R2, L2, F2, B2>`, where every piece is oriented and the
+/// E slice edges are in the E slice, then to solved using only moves of that
+/// subgroup. Each step is an IDA* search over coordinates, small numbers
+/// standing for one aspect of the cube, moved by table lookups and bounded
+/// by tables of how far each pair of coordinates is from solved.
+///
+/// Solutions are not the shortest possible, but are quick to find: once
+/// the tables are loaded, most take hundredths of a second in a release
+/// build, and the slowest a few tenths.
+pub struct TwoPhaseSolver {
+    twist: MoveTable,
+    flip: MoveTable,
+    slice: MoveTable,
+    twist_slice: PruningTable,
+    flip_slice: PruningTable,
+    solved_slice: usize,
+    corners: MoveTable,
+    edges: MoveTable,
+    slice_order: MoveTable,
+    corners_slice: PruningTable,
+    edges_slice: PruningTable,
+    phase_one_turns: CanonicalSequences,
+    phase_two_turns: CanonicalSequences,
+}
+
+impl Default for TwoPhaseSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TwoPhaseSolver {
+    /// Works out the move and pruning tables, which takes most of a second
+    /// in a release build. `standard` saves them to load next time instead.
+    pub fn new() -> Self {
+        TwoPhaseSolver::with_tables(|size, moves, get, set| MoveTable::new(size, moves, get, set), PruningTable::new)
+    }
+
+    /// A solver with tables of the right sizes, all still to be filled in.
+    fn empty() -> Self {
+        TwoPhaseSolver::with_tables(|size, moves, _, _| MoveTable::empty(size, moves.len()),
+                                    |first, _, second, _| PruningTable::empty(first, second))
+    }
+
+    fn with_tables(move_table: impl Fn(usize, &[Move], fn(&RubiksCube3x3) -> usize, fn(&mut RubiksCube3x3, usize)) -> MoveTable,
+                   pruning_table: impl Fn(&MoveTable, usize, &MoveTable, usize) -> PruningTable) -> Self {
+        let solved = RubiksCube3x3::new();
+        let solved_slice = slice(&solved);
+
+        let twist = move_table(2187, FACE_TURNS, self::twist, set_twist);
+        let flip = move_table(2048, FACE_TURNS, self::flip, set_flip);
+        let slice = move_table(SLICE_POSITIONS, FACE_TURNS, self::slice, set_slice);
+        let twist_slice = pruning_table(&twist, 0, &slice, solved_slice);
+        let flip_slice = pruning_table(&flip, 0, &slice, solved_slice);
+
+        let corners = move_table(factorial(8), &PHASE_TWO_TURNS, corner_permutation, set_corner_permutation);
+        let edges = move_table(factorial(8), &PHASE_TWO_TURNS, layer_edge_permutation, set_layer_edge_permutation);
+        let slice_order = move_table(factorial(4), &PHASE_TWO_TURNS, slice_permutation, set_slice_permutation);
+        let corners_slice = pruning_table(&corners, 0, &slice_order, 0);
+        let edges_slice = pruning_table(&edges, 0, &slice_order, 0);
+
+        TwoPhaseSolver {
+            twist,
+            flip,
+            slice,
+            twist_slice,
+            flip_slice,
+            solved_slice,
+            corners,
+            edges,
+            slice_order,
+            corners_slice,
+            edges_slice,
+            phase_one_turns: CanonicalSequences::new(FACE_TURNS),
+            phase_two_turns: CanonicalSequences::new(&PHASE_TWO_TURNS),
+        }
+    }
+
+    /// A solver with its tables kept in `tables`, and worked out and saved
+    /// there the first time.
+    pub fn standard(tables: &Path) -> io::Result<Self> {
+        fs::create_dir_all(tables)?;
+
+        TwoPhaseSolver::load_or_new(&tables.join("two-phase.tables"))
+    }
+
+    /// The tables, in the order `save` writes them.
+    fn tables_mut(&mut self) -> ([&mut MoveTable; 6], [&mut PruningTable; 4]) {
+        ([&mut self.twist, &mut self.flip, &mut self.slice, &mut self.corners, &mut self.edges, &mut self.slice_order],
+         [&mut self.twist_slice, &mut self.flip_slice, &mut self.corners_slice, &mut self.edges_slice])
+    }
+
+    /// Writes the tables to `path`: `MAGIC`, every move table, two bytes to
+    /// an entry, then every pruning table, a byte to an entry. It is
+    /// written alongside first and renamed, so a run stopped partway never
+    /// leaves half the tables.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut bytes = MAGIC.to_vec();
+
+        for table in [&self.twist, &self.flip, &self.slice, &self.corners, &self.edges, &self.slice_order] {
+            bytes.extend(table.to.iter().flat_map(|to| to.to_le_bytes()));
+        }
+        for table in [&self.twist_slice, &self.flip_slice, &self.corners_slice, &self.edges_slice] {
+            bytes.extend(&table.distances);
+        }
+
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, path)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+        let bytes = fs::read(path)?;
+        let mut rest = bytes.strip_prefix(MAGIC).ok_or_else(|| invalid("not two-phase tables"))?;
+        let mut take = |length: usize| -> io::Result<&[u8]> {
+            let (taken, tail) = rest.split_at_checked(length).ok_or_else(|| invalid("two-phase tables cut short"))?;
+            rest = tail;
+            Ok(taken)
+        };
+
+        let mut solver = TwoPhaseSolver::empty();
+        let (move_tables, pruning_tables) = solver.tables_mut();
+
+        for table in move_tables {
+            let size = table.size();
+            for (to, bytes) in table.to.iter_mut().zip(take(size * table.moves * 2)?.chunks(2)) {
+                *to = u16::from_le_bytes([bytes[0], bytes[1]]);
+                if *to as usize >= size {
+                    return Err(invalid("not a coordinate"));
+                }
+            }
+        }
+        for table in pruning_tables {
+            let length = table.distances.len();
+            table.distances.copy_from_slice(take(length)?);
+        }
+
+        if !rest.is_empty() {
+            return Err(invalid("two-phase tables are the wrong size"));
+        }
+
+        Ok(solver)
+    }
+
+    /// The tables saved at `path`, or if there are none yet, new ones,
+    /// saved there for next time.
+    pub fn load_or_new(path: &Path) -> io::Result<Self> {
+        match TwoPhaseSolver::load(path) {
+            Ok(solver) => Ok(solver),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                let solver = TwoPhaseSolver::new();
+                solver.save(path)?;
+                Ok(solver)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// A sequence of face turns of at most `DEFAULT_MAX_LENGTH` that solves
+    /// `rc`, after a rotation if slice moves have left its centres out of
+    /// place.
+    pub fn solve(&self, rc: &RubiksCube3x3) -> Option<Algorithm> {
+        self.solve_within(rc, DEFAULT_MAX_LENGTH)
+    }
+
+    /// A sequence of at most `max_length` face turns that solves `rc`, or
+    /// `None` if none turns up. The lower `max_length`, the longer the
+    /// search can take.
+    pub fn solve_within(&self, rc: &RubiksCube3x3, max_length: usize) -> Option<Algorithm> {
+        let (mut solution, rc) = upright(rc);
+        let start = PhaseOne { twist: twist(&rc), flip: flip(&rc), slice: slice(&rc) };
+        let mut moves = vec![];
+
+        (self.phase_one_bound(start)..=max_length)
+            .find(|&depth| self.phase_one(&rc, start, depth, max_length, &mut moves))?;
+
+        solution.0.extend(moves);
+        Some(solution)
+    }
+
+    fn phase_one_bound(&self, position: PhaseOne) -> usize {
+        self.twist_slice
+            .distance(position.twist, position.slice)
+            .max(self.flip_slice.distance(position.flip, position.slice))
+    }
+
+    /// Whether phase one can be finished in exactly `depth` more moves
+    /// after `moves` in a way that phase two can then finish within
+    /// `max_length` moves in all, leaving the moves of both on `moves`.
+    fn phase_one(&self,
+                 rc: &RubiksCube3x3,
+                 position: PhaseOne,
+                 depth: usize,
+                 max_length: usize,
+                 moves: &mut Vec<Move>) -> bool {
+        if depth == 0 {
+            // A phase one that ends with a phase two move could have
+            // stopped a move earlier, and was tried then.
+            let last_in_subgroup = moves.last().is_some_and(|m| PHASE_TWO_TURNS.contains(m));
+            return self.phase_one_bound(position) == 0 && !last_in_subgroup && self.start_phase_two(rc, max_length, moves);
+        }
+        if self.phase_one_bound(position) > depth {
+            return false;
+        }
+
+        for (i, &m) in FACE_TURNS.iter().enumerate() {
+            if !self.phase_one_turns.allows(moves, m) {
+                continue;
+            }
+
+            let next = PhaseOne {
+                twist: self.twist.apply(position.twist, i),
+                flip: self.flip.apply(position.flip, i),
+                slice: self.slice.apply(position.slice, i),
+            };
+
+            moves.push(m);
+            if self.phase_one(rc, next, depth - 1, max_length, moves) {
+                return true;
+            }
+            moves.pop();
+        }
+
+        false
+    }
+
+    fn start_phase_two(&self, rc: &RubiksCube3x3, max_length: usize, moves: &mut Vec<Move>) -> bool {
+        let mut reduced = *rc;
+        moves.iter().for_each(|&m| reduced.apply(m));
+        debug_assert_eq!(self.solved_slice, slice(&reduced));
+
+        let start = PhaseTwo {
+            corners: corner_permutation(&reduced),
+            edges: layer_edge_permutation(&reduced),
+            slice: slice_permutation(&reduced),
+        };
+        let longest = PHASE_TWO_MAX.min(max_length.saturating_sub(moves.len()));
+
+        (self.phase_two_bound(start)..=longest).any(|depth| self.phase_two(start, depth, moves))
+    }
+
+    fn phase_two_bound(&self, position: PhaseTwo) -> usize {
+        self.corners_slice
+            .distance(position.corners, position.slice)
+            .max(self.edges_slice.distance(position.edges, position.slice))
+    }
+
+    fn phase_two(&self, position: PhaseTwo, depth: usize, moves: &mut Vec<Move>) -> bool {
+        if depth == 0 {
+            return self.phase_two_bound(position) == 0;
+        }
+        if self.phase_two_bound(position) > depth {
+            return false;
+        }
+
+        for (i, &m) in PHASE_TWO_TURNS.iter().enumerate() {
+            if !self.phase_two_turns.allows(moves, m) {
+                continue;
+            }
+
+            let next = PhaseTwo {
+                corners: self.corners.apply(position.corners, i),
+                edges: self.edges.apply(position.edges, i),
+                slice: self.slice_order.apply(position.slice, i),
+            };
+
+            moves.push(m);
+            if self.phase_two(next, depth - 1, moves) {
+                return true;
+            }
+            moves.pop();
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use super::*;
+
+    fn solver() -> &'static TwoPhaseSolver {
+        static SOLVER: OnceLock<TwoPhaseSolver> = OnceLock::new();
+        SOLVER.get_or_init(TwoPhaseSolver::new)
+    }
+
+    fn scrambled(algorithm: &str) -> RubiksCube3x3 {
+        let mut rc = RubiksCube3x3::new();
+        algorithm.parse::<Algorithm>().unwrap().apply(&mut rc);
+        rc
+    }
+
+    #[test]
+    fn coordinates_read_back_what_was_set() {
+        let mut rc = RubiksCube3x3::new();
+
+        for coordinate in [0, 1, 100, 2186] {
+            set_twist(&mut rc, coordinate);
+            assert_eq!(coordinate, twist(&rc));
+        }
+        for coordinate in [0, 1, 1000, 2047] {
+            set_flip(&mut rc, coordinate);
+            assert_eq!(coordinate, flip(&rc));
+        }
+        for coordinate in [0, 69, 494] {
+            set_slice(&mut rc, coordinate);
+            assert_eq!(coordinate, slice(&rc));
+        }
+        for coordinate in [0, 5040, 40319] {
+            set_corner_permutation(&mut rc, coordinate);
+            assert_eq!(coordinate, corner_permutation(&rc));
+        }
+    }
+
+    #[test]
+    fn solved_cube_is_at_the_end_of_both_phases() {
+        let rc = RubiksCube3x3::new();
+
+        assert_eq!((0, 0, 494), (twist(&rc), flip(&rc), slice(&rc)));
+        assert_eq!((0, 0, 0), (corner_permutation(&rc), layer_edge_permutation(&rc), slice_permutation(&rc)));
+    }
+
+    #[test]
+    fn solutions_solve_within_max_length() {
+        let superflip = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+
+        for algorithm in ["", "R", "R U R' U'", "F R2 D' B L U2 R' F' D B2 L' U", superflip, "M E S x"] {
+            let mut rc = scrambled(algorithm);
+            let solution = solver().solve(&rc).unwrap();
+            solution.apply(&mut rc);
+
+            assert!(rc.solved(), "{} does not solve {}", solution, algorithm);
+            assert!(solution.iter().filter(|m| m.layer().kind() == LayerKind::Face).count() <= DEFAULT_MAX_LENGTH);
+        }
+    }
+
+    #[test]
+    fn phase_two_positions_only_need_phase_two_moves() {
+        let solution = solver().solve(&scrambled("U R2 D' F2 L2 U2")).unwrap();
+
+        assert!(solution.iter().all(|m| PHASE_TWO_TURNS.contains(m)), "{}", solution);
+    }
+
+    #[test]
+    fn save_then_load_gives_same_tables() {
+        let path = std::env::temp_dir().join(format!("two-phase-test-{}", std::process::id()));
+
+        solver().save(&path).unwrap();
+        let loaded = TwoPhaseSolver::load(&path).unwrap();
+        fs::write(&path, &fs::read(&path).unwrap()[..1000]).unwrap();
+        let cut_short = TwoPhaseSolver::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(loaded.twist.to == solver().twist.to && loaded.edges.to == solver().edges.to);
+        assert!(loaded.flip_slice.distances == solver().flip_slice.distances);
+        assert!(loaded.edges_slice.distances == solver().edges_slice.distances);
+        assert_eq!(solver().solve(&scrambled("R U F' L2 D B")), loaded.solve(&scrambled("R U F' L2 D B")));
+        assert_eq!(io::ErrorKind::InvalidData, cut_short.err().unwrap().kind());
+    }
+}